@@ -0,0 +1,18 @@
+//! Export, import and loader structure parsing for Windows PE images.
+//!
+//! The crate is `#![no_std]` and by default does not allocate, so it can run
+//! in loaders, drivers and other freestanding code. Every feature is opt-in:
+//!
+//! - `alloc`: pattern sets, export caches and RTTI class hierarchies.
+//! - `demangle`: MSVC and Itanium demangling, implies `alloc`.
+//! - `std`: `std::error::Error` for the error types, implies `alloc`.
+#![no_std]
+
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod windows;
+
+pub use windows::PortableExecutable;
@@ -1,6 +1,4 @@
-mod windows;
-
-use windows::PortableExecutable;
+use pe_exports::PortableExecutable;
 
 fn main() {
     if let Some(pe) = PortableExecutable::find("kernel32") {
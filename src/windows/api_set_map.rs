@@ -0,0 +1,549 @@
+use core::{
+    char::{decode_utf16, REPLACEMENT_CHARACTER},
+    ffi::c_void,
+    fmt::{self, Display, Formatter, Write},
+    mem::size_of,
+    slice::from_raw_parts,
+};
+
+use super::{Image, ModuleName, Peb};
+
+/// The API set schema that maps contracts such as
+/// `api-ms-win-core-synch-l1-2-0.dll` to the modules hosting them. Schema
+/// versions 2 (Windows 7), 4 (Windows 8.1) and 6 (Windows 10 and later) are
+/// understood.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiSetMap<'a> {
+    bytes: &'a [u8],
+    version: u32,
+    count: u32,
+}
+
+/// A contract of the schema together with the location of its hosts.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiSetEntry<'a> {
+    map: ApiSetMap<'a>,
+    name: ApiSetName<'a>,
+    hashed_length: usize,
+    values: usize,
+    value_count: u32,
+}
+
+/// A host of a contract. A non-empty `importer` restricts the host to that
+/// importing module, which is how e.g. `kernel32.dll` is redirected to
+/// `kernelbase.dll` instead of to itself.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiSetValue<'a> {
+    importer: ApiSetName<'a>,
+    host: ApiSetName<'a>,
+}
+
+/// A UTF-16LE string stored in the schema.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiSetName<'a> {
+    bytes: &'a [u8],
+}
+
+pub struct ApiSetEntryIterator<'a> {
+    map: ApiSetMap<'a>,
+    index: u32,
+}
+
+pub struct ApiSetValueIterator<'a> {
+    entry: ApiSetEntry<'a>,
+    index: u32,
+}
+
+fn dword(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes
+            .get(offset..offset.checked_add(size_of::<u32>())?)?
+            .try_into()
+            .ok()?,
+    ))
+}
+
+fn ascii_lowercase(unit: u16) -> u16 {
+    match unit {
+        0x41..=0x5a => unit + 0x20,
+        _ => unit,
+    }
+}
+
+impl<'a> ApiSetMap<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let version = dword(bytes, 0)?;
+        let (bytes, count) = match version {
+            2 => (bytes, dword(bytes, 4)?),
+            4 | 6 => (bytes.get(..dword(bytes, 4)? as usize)?, dword(bytes, 12)?),
+            _ => return None,
+        };
+        Some(Self {
+            bytes,
+            version,
+            count,
+        })
+    }
+
+    /// Parses the `.apiset` section of `apisetschema.dll`.
+    pub fn from_image(image: &Image<'a>) -> Option<Self> {
+        ApiSetMap::parse(image.section_by_name(".apiset")?.data(image)?)
+    }
+
+    /// Parses the schema the loader mapped at `map`, such as
+    /// `Peb::ApiSetMap`.
+    ///
+    /// # Safety
+    ///
+    /// `map` must point to a complete schema that stays mapped for the
+    /// lifetime of the process.
+    pub unsafe fn from_ptr(map: *const c_void) -> Option<ApiSetMap<'static>> {
+        if map.is_null() {
+            return None;
+        }
+        let dword = |offset: usize| -> usize {
+            unsafe {
+                (map as *const u8)
+                    .add(offset)
+                    .cast::<u32>()
+                    .read_unaligned() as usize
+            }
+        };
+        let size = match dword(0) {
+            2 => ApiSetMap::v2_size(dword),
+            4 | 6 => dword(4),
+            _ => return None,
+        };
+        ApiSetMap::parse(unsafe { from_raw_parts(map as *const u8, size) })
+    }
+
+    /// Version 2 does not record its size, so it is the furthest byte any
+    /// entry refers to.
+    fn v2_size(dword: impl Fn(usize) -> usize) -> usize {
+        let count = dword(4);
+        let mut size = 8 + count * 12;
+        for index in 0..count {
+            let entry = 8 + index * 12;
+            size = size.max(dword(entry) + dword(entry + 4));
+            let data = dword(entry + 8);
+            let value_count = dword(data);
+            size = size.max(data + 4 + value_count * 16);
+            for value in 0..value_count {
+                let value = data + 4 + value * 16;
+                size = size.max(dword(value) + dword(value + 4));
+                size = size.max(dword(value + 8) + dword(value + 12));
+            }
+        }
+        size
+    }
+
+    /// The schema of the current process.
+    pub fn current() -> Option<ApiSetMap<'static>> {
+        Peb::current().api_set_map()
+    }
+
+    pub fn version(self: &Self) -> &u32 {
+        &self.version
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.count.eq(&0)
+    }
+
+    pub fn entries(self: &Self) -> ApiSetEntryIterator<'a> {
+        ApiSetEntryIterator {
+            map: self.clone(),
+            index: 0,
+        }
+    }
+
+    pub fn entry(self: &Self, index: u32) -> Option<ApiSetEntry<'a>> {
+        if index.ge(&self.count) {
+            return None;
+        }
+        let index = index as usize;
+        let dword = |offset: usize| dword(self.bytes, offset).map(|value| value as usize);
+        let (name, name_length, hashed_length, values, value_count) = match self.version {
+            2 => {
+                let entry = 8 + index * 12;
+                let data = dword(entry + 8)?;
+                let name_length = dword(entry + 4)?;
+                (
+                    dword(entry)?,
+                    name_length,
+                    name_length,
+                    data + 4,
+                    dword(data)?,
+                )
+            }
+            4 => {
+                let entry = 16 + index * 24;
+                let data = dword(entry + 20)?;
+                let name_length = dword(entry + 8)?;
+                (
+                    dword(entry + 4)?,
+                    name_length,
+                    name_length,
+                    data + 8,
+                    dword(data + 4)?,
+                )
+            }
+            _ => {
+                let entry = dword(16)? + index * 24;
+                (
+                    dword(entry + 4)?,
+                    dword(entry + 8)?,
+                    dword(entry + 12)?,
+                    dword(entry + 16)?,
+                    dword(entry + 20)?,
+                )
+            }
+        };
+        Some(ApiSetEntry {
+            map: self.clone(),
+            name: self.name(name, name_length)?,
+            hashed_length,
+            values,
+            value_count: value_count as u32,
+        })
+    }
+
+    fn name(self: &Self, offset: usize, length: usize) -> Option<ApiSetName<'a>> {
+        let bytes = self.bytes.get(offset..offset.checked_add(length)?)?;
+        bytes
+            .len()
+            .is_multiple_of(size_of::<u16>())
+            .then_some(ApiSetName { bytes })
+    }
+
+    /// Finds the entry of `contract`, with or without its `.dll` extension.
+    /// Like the loader, version 6 ignores the minor version after the last
+    /// hyphen, so `api-ms-win-core-synch-l1-2-1` finds
+    /// `api-ms-win-core-synch-l1-2-0`.
+    pub fn find(self: &Self, contract: &str) -> Option<ApiSetEntry<'a>> {
+        let contract = match contract.get(contract.len().saturating_sub(4)..) {
+            Some(extension) if extension.eq_ignore_ascii_case(".dll") => {
+                &contract[..contract.len() - 4]
+            }
+            _ => contract,
+        };
+        let prefix = contract.get(..4)?;
+        if !prefix.eq_ignore_ascii_case("api-") && !prefix.eq_ignore_ascii_case("ext-") {
+            return None;
+        }
+        let key = match self.version {
+            6 => &contract[..contract.rfind('-')?],
+            _ => &contract[4..],
+        };
+        self.entries()
+            .find(|entry| entry.hashed_name().eq_ignore_ascii_case(key))
+    }
+
+    /// The module hosting `contract` for `importer`, the base name of the
+    /// importing module if known. `None` if `contract` is not an API set or
+    /// has no host.
+    pub fn resolve(
+        self: &Self,
+        contract: &str,
+        importer: Option<&ModuleName>,
+    ) -> Option<ApiSetName<'a>> {
+        self.find(contract)?.host(importer)
+    }
+}
+
+impl<'a> ApiSetEntry<'a> {
+    /// The contract name without `.dll`. Versions 2 and 4 also omit the
+    /// `api-` or `ext-` prefix.
+    pub fn name(self: &Self) -> &ApiSetName<'a> {
+        &self.name
+    }
+
+    /// The part of the name contracts are matched on.
+    pub fn hashed_name(self: &Self) -> ApiSetName<'a> {
+        ApiSetName {
+            bytes: self
+                .name
+                .bytes
+                .get(..self.hashed_length)
+                .unwrap_or(self.name.bytes),
+        }
+    }
+
+    pub fn values(self: &Self) -> ApiSetValueIterator<'a> {
+        ApiSetValueIterator {
+            entry: self.clone(),
+            index: 0,
+        }
+    }
+
+    pub fn value(self: &Self, index: u32) -> Option<ApiSetValue<'a>> {
+        if index.ge(&self.value_count) {
+            return None;
+        }
+        let map = &self.map;
+        let value = match map.version {
+            2 => self.values + index as usize * 16,
+            _ => self.values + index as usize * 20 + size_of::<u32>(),
+        };
+        let dword = |offset: usize| dword(map.bytes, offset).map(|value| value as usize);
+        Some(ApiSetValue {
+            importer: map.name(dword(value)?, dword(value + 4)?)?,
+            host: map.name(dword(value + 8)?, dword(value + 12)?)?,
+        })
+    }
+
+    /// The host for `importer`, falling back to the default host.
+    pub fn host(self: &Self, importer: Option<&ModuleName>) -> Option<ApiSetName<'a>> {
+        let exception = importer.and_then(|importer| {
+            self.values().find(|value| {
+                !value.importer.is_empty()
+                    && value.importer.eq_units_ignore_ascii_case(importer.units())
+            })
+        });
+        let value = match exception {
+            Some(value) => value,
+            None => self
+                .values()
+                .find(|value| value.importer.is_empty())
+                .or_else(|| self.value(0))?,
+        };
+        (!value.host.is_empty()).then_some(value.host)
+    }
+}
+
+impl<'a> ApiSetValue<'a> {
+    pub fn importer(self: &Self) -> &ApiSetName<'a> {
+        &self.importer
+    }
+
+    pub fn host(self: &Self) -> &ApiSetName<'a> {
+        &self.host
+    }
+}
+
+impl<'a> ApiSetName<'a> {
+    pub fn units(self: &Self) -> impl Iterator<Item = u16> + use<'a> {
+        self.bytes
+            .chunks_exact(size_of::<u16>())
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.bytes.len() / size_of::<u16>()
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn eq_ignore_ascii_case(self: &Self, other: &str) -> bool {
+        self.units()
+            .map(ascii_lowercase)
+            .eq(other.encode_utf16().map(ascii_lowercase))
+    }
+
+    pub fn eq_units_ignore_ascii_case(self: &Self, other: impl IntoIterator<Item = u16>) -> bool {
+        self.units()
+            .map(ascii_lowercase)
+            .eq(other.into_iter().map(ascii_lowercase))
+    }
+}
+
+impl<'a> Iterator for ApiSetEntryIterator<'a> {
+    type Item = ApiSetEntry<'a>;
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        let entry = self.map.entry(self.index)?;
+        self.index += 1;
+        Some(entry)
+    }
+}
+
+impl<'a> Iterator for ApiSetValueIterator<'a> {
+    type Item = ApiSetValue<'a>;
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        let value = self.entry.value(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+}
+
+impl Display for ApiSetName<'_> {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        for character in decode_utf16(self.units()) {
+            f.write_char(character.unwrap_or(REPLACEMENT_CHARACTER))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec::Vec};
+
+    use super::{ApiSetMap, ModuleName};
+    use crate::windows::test_image::api_set_schema;
+
+    const SYNCH: &str = "api-ms-win-core-synch-l1-2-0";
+
+    /// A version 6 schema laid out by hand: the header, one entry, one value
+    /// and the names `api-ms-win-core-synch-l1-2-0` and `kernelbase.dll`.
+    fn v6_fixture() -> Vec<u8> {
+        let dwords: [u32; 18] = [
+            // Version, size, flags, count, entries, hashes, hash factor.
+            6, 156, 0, 1, 28, 0, 0x1f,
+            // Flags, name, name length, hashed length, values, value count.
+            1, 72, 56, 52, 52, 1, // Flags, importer, importer length, host, host length.
+            0, 0, 0, 128, 28,
+        ];
+        let mut bytes: Vec<u8> = dwords
+            .iter()
+            .flat_map(|dword| dword.to_le_bytes())
+            .collect();
+        for name in [SYNCH, "kernelbase.dll"] {
+            bytes.extend(name.encode_utf16().flat_map(u16::to_le_bytes));
+        }
+        assert_eq!(bytes.len(), 156);
+        bytes
+    }
+
+    #[test]
+    fn parses_a_v6_fixture() {
+        let bytes = v6_fixture();
+        let map = ApiSetMap::parse(&bytes).unwrap();
+        assert_eq!(map.version(), &6);
+        assert_eq!(map.len(), 1);
+
+        let entry = map.entry(0).unwrap();
+        assert_eq!(entry.name().to_string(), SYNCH);
+        assert_eq!(
+            entry.hashed_name().to_string(),
+            "api-ms-win-core-synch-l1-2"
+        );
+        assert!(map.entry(1).is_none());
+
+        let value = entry.value(0).unwrap();
+        assert!(value.importer().is_empty());
+        assert_eq!(value.host().to_string(), "kernelbase.dll");
+        assert!(entry.value(1).is_none());
+    }
+
+    #[test]
+    fn finds_v6_contracts_like_the_loader() {
+        let bytes = v6_fixture();
+        let map = ApiSetMap::parse(&bytes).unwrap();
+        for contract in [
+            "api-ms-win-core-synch-l1-2-0",
+            "api-ms-win-core-synch-l1-2-0.dll",
+            "API-MS-WIN-CORE-SYNCH-L1-2-0.DLL",
+            // Only the minor version differs.
+            "api-ms-win-core-synch-l1-2-1.dll",
+        ] {
+            let host = map.resolve(contract, None).unwrap();
+            assert_eq!(format!("{}", host), "kernelbase.dll", "{}", contract);
+        }
+        for contract in [
+            "api-ms-win-core-synch-l1-1-0.dll",
+            "api-ms-win-core-synch.dll",
+            "kernel32.dll",
+            "api",
+            "",
+        ] {
+            assert!(map.find(contract).is_none(), "{}", contract);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_schemas() {
+        let bytes = v6_fixture();
+        assert!(ApiSetMap::parse(&bytes[..155]).is_none());
+        assert!(ApiSetMap::parse(&bytes[..3]).is_none());
+
+        let mut unknown = bytes.clone();
+        unknown[0] = 5;
+        assert!(ApiSetMap::parse(&unknown).is_none());
+
+        // An odd name length, and a host past the end of the schema.
+        let mut odd = bytes.clone();
+        odd[36] = 55;
+        assert!(ApiSetMap::parse(&odd).unwrap().entry(0).is_none());
+        let mut outside = bytes.clone();
+        outside[64] = 0xff;
+        let map = ApiSetMap::parse(&outside).unwrap();
+        assert!(map.entry(0).unwrap().value(0).is_none());
+        assert!(map.resolve(SYNCH, None).is_none());
+    }
+
+    #[test]
+    fn resolves_every_version_alike() {
+        let values: &[(&str, &str)] = &[("", "kernel32.dll"), ("kernel32.dll", "kernelbase.dll")];
+        for version in [2, 4, 6] {
+            // Versions 2 and 4 store names without their prefix.
+            let stored = |name: &'static str| match version {
+                6 => name,
+                _ => &name[4..],
+            };
+            let name = stored(SYNCH);
+            let bytes = api_set_schema(
+                version,
+                &[
+                    (stored("ext-ms-win-ntuser-window-l1-1-0"), &[]),
+                    (name, values),
+                ],
+            );
+            let map = ApiSetMap::parse(&bytes).unwrap();
+            assert_eq!(map.version(), &version);
+            assert_eq!(map.entries().count(), 2);
+            assert_eq!(map.entries().nth(1).unwrap().name().to_string(), name);
+
+            let kernel32 = ModuleName::Utf8("KERNEL32.DLL");
+            let user32 = ModuleName::Utf8("user32.dll");
+            let host = |importer| map.resolve("api-ms-win-core-synch-l1-2-0.dll", importer);
+            assert_eq!(host(None).unwrap().to_string(), "kernel32.dll");
+            assert_eq!(host(Some(&user32)).unwrap().to_string(), "kernel32.dll");
+            assert_eq!(host(Some(&kernel32)).unwrap().to_string(), "kernelbase.dll");
+
+            // A contract without hosts is known but resolves to nothing.
+            assert!(map.find("ext-ms-win-ntuser-window-l1-1-0").is_some());
+            assert!(map
+                .resolve("ext-ms-win-ntuser-window-l1-1-0", None)
+                .is_none());
+
+            // Only version 6 ignores the minor version.
+            let newer = map.find("api-ms-win-core-synch-l1-2-1");
+            assert_eq!(newer.is_some(), version.eq(&6), "version {}", version);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_the_first_host() {
+        let bytes = api_set_schema(6, &[(SYNCH, &[("user32.dll", "kernelbase.dll")])]);
+        let map = ApiSetMap::parse(&bytes).unwrap();
+        assert_eq!(
+            map.resolve(SYNCH, None).unwrap().to_string(),
+            "kernelbase.dll"
+        );
+
+        let bytes = api_set_schema(6, &[(SYNCH, &[("", "")])]);
+        let map = ApiSetMap::parse(&bytes).unwrap();
+        assert!(map.resolve(SYNCH, None).is_none());
+    }
+
+    #[test]
+    fn sizes_mapped_schemas() {
+        for version in [2, 4, 6] {
+            let bytes = api_set_schema(
+                version,
+                &[("ms-win-core-synch-l1-2-0", &[("", "kernel32.dll")])],
+            );
+            let map = unsafe { ApiSetMap::from_ptr(bytes.as_ptr().cast()) }.unwrap();
+            assert_eq!(map.bytes.len(), bytes.len(), "version {}", version);
+        }
+        assert!(unsafe { ApiSetMap::from_ptr(core::ptr::null()) }.is_none());
+    }
+}
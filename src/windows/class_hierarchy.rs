@@ -0,0 +1,464 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{self, Display, Formatter};
+
+use super::{
+    Rtti, RttiBaseClass, RttiBaseClassDescriptor, RttiClassHierarchyDescriptor, RttiInheritance,
+    RttiTypeDescriptor,
+};
+
+/// Every class with RTTI in an image and the inheritance edges between them,
+/// as recorded by the compiler in the class hierarchy descriptors.
+#[derive(Debug, Clone)]
+pub struct ClassHierarchy<'a> {
+    classes: Vec<ClassInfo<'a>>,
+    /// For each class, the classes listing it as a direct base.
+    derived: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassInfo<'a> {
+    decorated_name: &'a str,
+    type_descriptor: u32,
+    attributes: Option<u32>,
+    bases: Vec<BaseClass>,
+    complete_object_locators: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BaseClass {
+    class: usize,
+    mdisp: i32,
+    pdisp: i32,
+    vdisp: i32,
+    attributes: u32,
+}
+
+/// A class and the classes deriving from it. Classes with several bases
+/// show up once under each of them, but only the first occurrence lists the
+/// classes deriving from them in turn.
+#[derive(Debug, Clone)]
+pub struct ClassNode {
+    class: usize,
+    children: Vec<ClassNode>,
+}
+
+/// Formats `.?AVInner@Outer@@` as `Outer::Inner`.
+#[derive(Debug, Clone, Copy)]
+pub struct UndecoratedClassName<'a>(&'a str);
+
+impl<'a> ClassHierarchy<'a> {
+    /// Real hierarchies are a few levels deep; deeper classes are left out
+    /// of [`ClassHierarchy::tree`].
+    pub const MAX_TREE_DEPTH: usize = 256;
+
+    pub fn new(rtti: &Rtti<'a>) -> Self {
+        let mut hierarchy = Self {
+            classes: Vec::new(),
+            derived: Vec::new(),
+        };
+        let mut indices = BTreeMap::new();
+        for type_descriptor in rtti.type_descriptors() {
+            hierarchy.class_index(rtti, &mut indices, type_descriptor);
+        }
+
+        // Hierarchy descriptors reference each other through their bases;
+        // each is visited once so malformed images cannot loop.
+        let mut visited = BTreeSet::new();
+        let mut pending = Vec::new();
+
+        for rva in rtti.complete_object_locators() {
+            let locator = match rtti.complete_object_locator(rva) {
+                Some(locator) => locator,
+                None => continue,
+            };
+            let class =
+                hierarchy.class_index(rtti, &mut indices, locator.type_descriptor().clone());
+            hierarchy.classes[class].complete_object_locators.push(rva);
+            pending.push((class, locator.class_descriptor().clone()));
+            while let Some((class, class_descriptor)) = pending.pop() {
+                if !visited.insert(class_descriptor) {
+                    continue;
+                }
+                if let Some(class_hierarchy_descriptor) =
+                    rtti.class_hierarchy_descriptor(class_descriptor)
+                {
+                    hierarchy.add_bases(
+                        rtti,
+                        &mut indices,
+                        &mut pending,
+                        class,
+                        class_hierarchy_descriptor,
+                    );
+                }
+            }
+        }
+        hierarchy.index_derived();
+        hierarchy
+    }
+
+    fn index_derived(self: &mut Self) {
+        self.derived = vec![Vec::new(); self.classes.len()];
+        for (derived, class) in self.classes.iter().enumerate() {
+            for base in &class.bases {
+                // A class listing the same base twice derives from it once.
+                let children = &mut self.derived[base.class];
+                if children.last().ne(&Some(&derived)) {
+                    children.push(derived);
+                }
+            }
+        }
+    }
+
+    fn class_index(
+        self: &mut Self,
+        rtti: &Rtti<'a>,
+        indices: &mut BTreeMap<u32, usize>,
+        type_descriptor: u32,
+    ) -> usize {
+        *indices.entry(type_descriptor).or_insert_with(|| {
+            self.classes.push(ClassInfo {
+                decorated_name: rtti.type_name(type_descriptor).unwrap_or(""),
+                type_descriptor,
+                attributes: None,
+                bases: Vec::new(),
+                complete_object_locators: Vec::new(),
+            });
+            self.classes.len() - 1
+        })
+    }
+
+    fn add_bases(
+        self: &mut Self,
+        rtti: &Rtti<'a>,
+        indices: &mut BTreeMap<u32, usize>,
+        pending: &mut Vec<(usize, u32)>,
+        class: usize,
+        class_hierarchy_descriptor: &RttiClassHierarchyDescriptor,
+    ) {
+        if self.classes[class].attributes.is_some() {
+            return;
+        }
+        self.classes[class].attributes = Some(class_hierarchy_descriptor.attributes().clone());
+
+        // The array is a pre-order walk of the hierarchy that starts with the
+        // class itself; skipping the bases contained in an entry lands on the
+        // next direct base.
+        let descriptors: Vec<&RttiBaseClassDescriptor> = rtti
+            .base_class_descriptors(class_hierarchy_descriptor)
+            .collect();
+        let mut index = 1;
+        while let Some(descriptor) = descriptors.get(index) {
+            let base = self.class_index(rtti, indices, descriptor.type_descriptor().clone());
+            let pmd = descriptor.pmd();
+            self.classes[class].bases.push(BaseClass {
+                class: base,
+                mdisp: pmd.mdisp().clone(),
+                pdisp: pmd.pdisp().clone(),
+                vdisp: pmd.vdisp().clone(),
+                attributes: descriptor.attributes().clone(),
+            });
+            if descriptor.has_attribute(RttiBaseClass::HasHierarchyDescriptor) {
+                pending.push((base, descriptor.class_descriptor().clone()));
+            }
+            index += descriptor.number_of_contained_bases().clone() as usize + 1;
+        }
+    }
+
+    pub fn classes(self: &Self) -> &[ClassInfo<'a>] {
+        &self.classes
+    }
+
+    pub fn class(self: &Self, index: usize) -> Option<&ClassInfo<'a>> {
+        self.classes.get(index)
+    }
+
+    pub fn find(self: &Self, class_name: &str) -> Option<usize> {
+        self.classes.iter().position(|class| {
+            RttiTypeDescriptor::is_decorated_name_of(class.decorated_name, class_name)
+        })
+    }
+
+    /// Indices of the classes that list `index` as a direct base.
+    pub fn derived(self: &Self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.derived
+            .get(index)
+            .map_or(&[][..], |derived| derived.as_slice())
+            .iter()
+            .cloned()
+    }
+
+    pub fn roots(self: &Self) -> impl Iterator<Item = usize> + '_ {
+        self.classes
+            .iter()
+            .enumerate()
+            .filter(|(_, class)| class.bases.is_empty())
+            .map(|(index, _)| index)
+    }
+
+    /// The hierarchy as a forest rooted at the classes without bases, at
+    /// most [`ClassHierarchy::MAX_TREE_DEPTH`] levels deep.
+    pub fn tree(self: &Self) -> Vec<ClassNode> {
+        let mut expanded = BTreeSet::new();
+        self.roots()
+            .map(|root| self.node(root, 0, &mut expanded))
+            .collect()
+    }
+
+    /// Expanding every class once keeps diamonds from multiplying the tree
+    /// and malformed hierarchies from looping back on themselves; the depth
+    /// limit bounds the recursion for chains crafted to be long.
+    fn node(self: &Self, class: usize, depth: usize, expanded: &mut BTreeSet<usize>) -> ClassNode {
+        let expand = depth.lt(&(ClassHierarchy::MAX_TREE_DEPTH - 1)) && expanded.insert(class);
+        let children = match expand {
+            true => self
+                .derived(class)
+                .map(|derived| self.node(derived, depth + 1, expanded))
+                .collect(),
+            false => Vec::new(),
+        };
+        ClassNode { class, children }
+    }
+}
+
+impl<'a> ClassInfo<'a> {
+    pub fn decorated_name(self: &Self) -> &'a str {
+        self.decorated_name
+    }
+
+    pub fn name(self: &Self) -> UndecoratedClassName<'a> {
+        UndecoratedClassName(self.decorated_name)
+    }
+
+    pub fn type_descriptor(self: &Self) -> &u32 {
+        &self.type_descriptor
+    }
+
+    /// `None` for classes no hierarchy descriptor was found for.
+    pub fn attributes(self: &Self) -> Option<u32> {
+        self.attributes
+    }
+
+    pub fn has_attribute(self: &Self, attribute: RttiInheritance) -> bool {
+        self.attributes
+            .is_some_and(|attributes| attributes & attribute as u32 != 0)
+    }
+
+    /// Direct bases in declaration order.
+    pub fn bases(self: &Self) -> &[BaseClass] {
+        &self.bases
+    }
+
+    /// One locator per vtable of the class; empty for classes that are not
+    /// polymorphic.
+    pub fn complete_object_locators(self: &Self) -> &[u32] {
+        &self.complete_object_locators
+    }
+}
+
+impl BaseClass {
+    pub fn class(self: &Self) -> &usize {
+        &self.class
+    }
+
+    pub fn mdisp(self: &Self) -> &i32 {
+        &self.mdisp
+    }
+
+    pub fn pdisp(self: &Self) -> &i32 {
+        &self.pdisp
+    }
+
+    pub fn vdisp(self: &Self) -> &i32 {
+        &self.vdisp
+    }
+
+    pub fn attributes(self: &Self) -> &u32 {
+        &self.attributes
+    }
+
+    pub fn is_virtual(self: &Self) -> bool {
+        self.pdisp.ne(&-1)
+    }
+}
+
+impl ClassNode {
+    pub fn class(self: &Self) -> &usize {
+        &self.class
+    }
+
+    pub fn children(self: &Self) -> &[ClassNode] {
+        &self.children
+    }
+}
+
+impl Display for UndecoratedClassName<'_> {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        let body = self
+            .0
+            .strip_prefix(".?AV")
+            .or_else(|| self.0.strip_prefix(".?AU"))
+            .and_then(|body| body.strip_suffix("@@"));
+        match body {
+            // Template arguments keep their decorated form.
+            Some(body) if !body.contains('?') => {
+                for (index, component) in body.rsplit('@').enumerate() {
+                    if index.ne(&0) {
+                        f.write_str("::")?;
+                    }
+                    f.write_str(component)?;
+                }
+                Ok(())
+            }
+            _ => f.write_str(self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    use super::{BaseClass, ClassHierarchy, ClassInfo, ClassNode};
+    use crate::windows::{
+        test_image::{TestImage, TestRtti},
+        Image, Rtti,
+    };
+
+    fn hierarchy(bases: Vec<Vec<usize>>) -> ClassHierarchy<'static> {
+        let classes = bases
+            .into_iter()
+            .map(|bases| ClassInfo {
+                decorated_name: "",
+                type_descriptor: 0,
+                attributes: Some(0),
+                bases: bases
+                    .into_iter()
+                    .map(|class| BaseClass {
+                        class,
+                        mdisp: 0,
+                        pdisp: -1,
+                        vdisp: 0,
+                        attributes: 0,
+                    })
+                    .collect(),
+                complete_object_locators: Vec::new(),
+            })
+            .collect();
+        let mut hierarchy = ClassHierarchy {
+            classes,
+            derived: Vec::new(),
+        };
+        hierarchy.index_derived();
+        hierarchy
+    }
+
+    fn count(nodes: &[ClassNode]) -> usize {
+        nodes.iter().map(|node| 1 + count(node.children())).sum()
+    }
+
+    #[test]
+    fn tree_expands_diamonds_once() {
+        // Twenty stacked diamonds: 3n+1 and 3n+2 derive from 3n, and 3n+3
+        // derives from both.
+        let mut bases = vec![vec![]];
+        for layer in 0..20 {
+            bases.push(vec![layer * 3]);
+            bases.push(vec![layer * 3]);
+            bases.push(vec![layer * 3 + 1, layer * 3 + 2]);
+        }
+        let tree = hierarchy(bases).tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(count(&tree), 1 + 20 * 4);
+    }
+
+    #[test]
+    fn tree_stops_at_cycles() {
+        // 1 and 2 derive from each other, and 1 from the root as well.
+        let tree = hierarchy(vec![vec![], vec![0, 2], vec![1]]).tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].children().len(), 1);
+        assert_eq!(count(&tree), 4);
+    }
+
+    #[test]
+    fn tree_is_depth_limited() {
+        let bases = (0..1000)
+            .map(|class| match class {
+                0 => vec![],
+                _ => vec![class - 1],
+            })
+            .collect();
+        let tree = hierarchy(bases).tree();
+        assert_eq!(count(&tree), ClassHierarchy::MAX_TREE_DEPTH);
+    }
+
+    #[test]
+    fn derived_lists_direct_subclasses_once() {
+        let hierarchy = hierarchy(vec![vec![], vec![0, 0], vec![0, 1], vec![2]]);
+        let derived: Vec<Vec<usize>> = (0..5)
+            .map(|class| hierarchy.derived(class).collect())
+            .collect();
+        assert_eq!(derived, vec![vec![1, 2], vec![2], vec![3], vec![], vec![]]);
+    }
+
+    #[test]
+    fn reads_direct_bases_from_the_rtti() {
+        // `Derived` derives from `Middle`, which derives from `Base`, and
+        // from `Base` again. Its base class array is `Derived, Middle, Base,
+        // Base`, so the second `Base` is only reached by skipping the bases
+        // contained in `Middle`.
+        let test_rtti = TestRtti::new(0x2000, 0x1_8000_0000)
+            .class(".?AVBase@@", &[], &[0x1800])
+            .class(".?AVMiddle@@", &[0], &[0x1800])
+            .class(".?AVDerived@@", &[1, 0], &[0x1800]);
+        let bytes = TestImage::new("rtti.dll")
+            .section(b".rdata", 0x2000, 0x4000_0040)
+            .contents(0x2000, test_rtti.bytes())
+            .build();
+        let hierarchy = Rtti::new(Image::from_file(&bytes).unwrap()).class_hierarchy();
+
+        let base = hierarchy.find("Base").unwrap();
+        let middle = hierarchy.find("Middle").unwrap();
+        let derived = hierarchy.find("Derived").unwrap();
+        let bases = |class: usize| -> Vec<usize> {
+            let class = hierarchy.class(class).unwrap();
+            class
+                .bases()
+                .iter()
+                .map(|base| base.class().clone())
+                .collect()
+        };
+        assert_eq!(bases(base), vec![]);
+        assert_eq!(bases(middle), vec![base]);
+        assert_eq!(bases(derived), vec![middle, base]);
+
+        let class = hierarchy.class(derived).unwrap();
+        assert_eq!(class.name().to_string(), "Derived");
+        assert_eq!(class.attributes(), Some(0));
+        assert_eq!(
+            class.complete_object_locators(),
+            &[test_rtti.classes()[2].complete_object_locator]
+        );
+        let base_class = class.bases()[1];
+        assert_eq!(
+            (
+                base_class.mdisp().clone(),
+                base_class.pdisp().clone(),
+                base_class.vdisp().clone()
+            ),
+            (0, -1, 0)
+        );
+        assert_eq!(base_class.attributes(), &0x40);
+        assert!(!base_class.is_virtual());
+
+        let tree = hierarchy.tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].class(), &base);
+        // `Derived` once under `Middle` and once directly under `Base`.
+        assert_eq!(count(&tree), 4);
+    }
+}
@@ -0,0 +1,45 @@
+use alloc::string::String;
+use core::fmt::{self, Display, Formatter};
+
+use super::{ItaniumDemangler, MsvcDemangler};
+
+/// An undecorated C++ symbol: the qualified name on its own and the full
+/// signature with access, calling convention and parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemangledName {
+    name: String,
+    signature: String,
+}
+
+/// Undecorates an MSVC (`?Foo@Bar@@QEAAXH@Z`) or Itanium (`_ZN3Bar3FooEi`)
+/// symbol. Returns `None` for plain C names and for manglings the demangler
+/// does not understand.
+pub fn demangle(symbol: &str) -> Option<DemangledName> {
+    match symbol.as_bytes() {
+        [b'?', ..] => MsvcDemangler::demangle(symbol),
+        [b'_', b'Z', ..] | [b'_', b'_', b'Z', ..] => ItaniumDemangler::demangle(symbol),
+        _ => None,
+    }
+}
+
+impl DemangledName {
+    pub fn new(name: String, signature: String) -> Self {
+        Self { name, signature }
+    }
+
+    /// `Bar::Foo`
+    pub fn name(self: &Self) -> &str {
+        &self.name
+    }
+
+    /// `public: void __cdecl Bar::Foo(int)` or `Bar::Foo(int)`
+    pub fn signature(self: &Self) -> &str {
+        &self.signature
+    }
+}
+
+impl Display for DemangledName {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.signature)
+    }
+}
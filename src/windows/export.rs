@@ -0,0 +1,170 @@
+#[cfg(any(feature = "demangle", test))]
+use super::{demangle, DemangledName};
+use super::{Image, ImageDataDirectory, ImageDirectoryEntry};
+
+/// A named entry of the export table.
+#[derive(Debug, Clone, Copy)]
+pub struct Export<'a> {
+    name: &'a str,
+    ordinal: u32,
+    rva: u32,
+    forwarder: Option<&'a str>,
+}
+
+/// Walks the export name table in order, which the linker keeps sorted by name.
+pub struct ExportIterator<'a> {
+    image: Image<'a>,
+    data_directory: &'a ImageDataDirectory,
+    base: u32,
+    names: &'a [[u8; 4]],
+    name_ordinals: &'a [[u8; 2]],
+    functions: &'a [[u8; 4]],
+    index: usize,
+}
+
+impl<'a> Export<'a> {
+    pub fn name(self: &Self) -> &'a str {
+        self.name
+    }
+
+    /// The biased ordinal, as used by `GetProcAddress` and `.def` files.
+    pub fn ordinal(self: &Self) -> &u32 {
+        &self.ordinal
+    }
+
+    pub fn rva(self: &Self) -> &u32 {
+        &self.rva
+    }
+
+    /// `module.function` or `module.#ordinal` when the export lives in
+    /// another module.
+    pub fn forwarder(self: &Self) -> Option<&'a str> {
+        self.forwarder
+    }
+
+    pub fn is_forwarded(self: &Self) -> bool {
+        self.forwarder.is_some()
+    }
+
+    #[cfg(any(feature = "demangle", test))]
+    pub fn undecorated_name(self: &Self) -> Option<DemangledName> {
+        demangle(self.name)
+    }
+
+    /// Whether `name` is this export's undecorated name, either qualified
+    /// (`Bar::Foo`) or as the full signature.
+    #[cfg(any(feature = "demangle", test))]
+    pub fn has_undecorated_name(self: &Self, name: &str) -> bool {
+        self.undecorated_name().is_some_and(|undecorated_name| {
+            undecorated_name.name().eq(name) || undecorated_name.signature().eq(name)
+        })
+    }
+}
+
+impl<'a> ExportIterator<'a> {
+    pub fn new(image: &Image<'a>) -> Self {
+        let data_directory = image.data_directory(ImageDirectoryEntry::Export);
+        let mut iterator = Self {
+            image: image.clone(),
+            data_directory,
+            base: 0,
+            names: &[],
+            name_ordinals: &[],
+            functions: &[],
+            index: 0,
+        };
+        if let Some(export_directory) = image.export_directory() {
+            let number_of_names = export_directory.number_of_names().clone() as usize;
+            let names = image.array_at_rva::<[u8; 4]>(
+                export_directory.address_of_names_rva().clone(),
+                number_of_names,
+            );
+            let name_ordinals = image.array_at_rva::<[u8; 2]>(
+                export_directory.address_of_name_ordinals_rva().clone(),
+                number_of_names,
+            );
+            let functions = image.array_at_rva::<[u8; 4]>(
+                export_directory.address_of_functions_rva().clone(),
+                export_directory.number_of_functions().clone() as usize,
+            );
+            if let (Some(names), Some(name_ordinals), Some(functions)) =
+                (names, name_ordinals, functions)
+            {
+                iterator.base = export_directory.base().clone();
+                iterator.names = names;
+                iterator.name_ordinals = name_ordinals;
+                iterator.functions = functions;
+            }
+        }
+        iterator
+    }
+}
+
+impl<'a> Iterator for ExportIterator<'a> {
+    type Item = Export<'a>;
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        while self.index.lt(&self.names.len()) {
+            let index = self.index;
+            self.index += 1;
+
+            let name = match self.image.str_at_rva(u32::from_le_bytes(self.names[index])) {
+                Some(name) => name,
+                None => continue,
+            };
+            let function_index = u16::from_le_bytes(self.name_ordinals[index]) as usize;
+            let rva = match self.functions.get(function_index) {
+                Some(rva) => u32::from_le_bytes(rva.clone()),
+                None => continue,
+            };
+            let forwarder = self
+                .data_directory
+                .is_forwarded_export(rva)
+                .then(|| self.image.str_at_rva(rva))
+                .flatten();
+            return Some(Export {
+                name,
+                ordinal: self.base.wrapping_add(function_index as u32),
+                rva,
+                forwarder,
+            });
+        }
+        None
+    }
+
+    fn size_hint(self: &Self) -> (usize, Option<usize>) {
+        (0, Some(self.names.len() - self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use crate::windows::{test_image::TestImage, Image};
+
+    #[test]
+    fn ordinals_wrap_around_past_the_largest_base() {
+        let bytes = TestImage::new("sample.dll")
+            .ordinal_base(u32::MAX)
+            .export("First", 0x2000)
+            .export("Second", 0x2010)
+            .forward("Third", "other.Function")
+            .build();
+        let image = Image::from_file(&bytes).unwrap();
+
+        let exports: Vec<(&str, u32)> = image
+            .exports()
+            .map(|export| (export.name(), export.ordinal().clone()))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![("First", u32::MAX), ("Second", 0), ("Third", 1)]
+        );
+        let functions: Vec<(u32, u32)> = image.export_functions().take(2).collect();
+        assert_eq!(functions, vec![(u32::MAX, 0x2000), (0, 0x2010)]);
+        assert_eq!(image.export_rva_by_ordinal(u32::MAX), Some(0x2000));
+        assert_eq!(image.export_rva_by_ordinal(0), Some(0x2010));
+        assert_eq!(image.export_rva_by_ordinal(2), None);
+    }
+}
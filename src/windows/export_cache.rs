@@ -0,0 +1,380 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::ffi::c_void;
+
+use super::{HashAlgorithm, Module, ModuleSource};
+
+/// Remembers resolved export addresses, forwarders included, per module so
+/// that repeated lookups skip the export name table and the modules of the
+/// [`ModuleSource`]. Entries of a module are dropped once it is looked up
+/// with another `dll_base` or `time_date_stamp`. Hits are served without
+/// checking that the modules are still loaded; [`ExportCache::prune`] does
+/// that once per cached module, e.g. after modules were unloaded.
+#[derive(Debug, Default)]
+pub struct ExportCache {
+    modules: Vec<ModuleExports>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKey<'a> {
+    Name(&'a str),
+    Ordinal(u32),
+    Hash(u64, HashAlgorithm),
+}
+
+#[derive(Debug)]
+struct ModuleExports {
+    module: LoadedModule,
+    names: BTreeMap<String, CachedExport>,
+    ordinals: BTreeMap<u32, CachedExport>,
+    hashes: BTreeMap<(u64, HashAlgorithm), CachedExport>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct LoadedModule {
+    dll_base: *const c_void,
+    time_date_stamp: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedExport {
+    address: *const c_void,
+    /// The module a forwarder led to, when it is not the exporting one.
+    target: Option<LoadedModule>,
+}
+
+impl ExportCache {
+    pub fn new() -> Self {
+        Self {
+            modules: Vec::new(),
+        }
+    }
+
+    pub fn export_function<'a, S: ModuleSource + ?Sized>(
+        self: &mut Self,
+        source: &'a S,
+        module: &Module<'a>,
+        name: &str,
+    ) -> *const c_void {
+        self.resolve(source, module, ExportKey::Name(name))
+    }
+
+    pub fn export_by_ordinal<'a, S: ModuleSource + ?Sized>(
+        self: &mut Self,
+        source: &'a S,
+        module: &Module<'a>,
+        ordinal: u32,
+    ) -> *const c_void {
+        self.resolve(source, module, ExportKey::Ordinal(ordinal))
+    }
+
+    pub fn export_by_hash<'a, S: ModuleSource + ?Sized>(
+        self: &mut Self,
+        source: &'a S,
+        module: &Module<'a>,
+        hash: u64,
+        algorithm: HashAlgorithm,
+    ) -> *const c_void {
+        self.resolve(source, module, ExportKey::Hash(hash, algorithm))
+    }
+
+    /// Looks `key` up in the cache of `module`, resolving it against
+    /// `source` and remembering it on a miss. Failed lookups are not
+    /// remembered, nor are forwarders that lead outside of every module of
+    /// `source`.
+    pub fn resolve<'a, S: ModuleSource + ?Sized>(
+        self: &mut Self,
+        source: &'a S,
+        module: &Module<'a>,
+        key: ExportKey,
+    ) -> *const c_void {
+        let exports = self.module(module);
+        if let Some(export) = exports.get(&key) {
+            return export.address;
+        }
+
+        let address = ExportCache::lookup(source, module, &key);
+        if address.is_null() {
+            return address;
+        }
+        let target = match module.contains_address(address) {
+            true => Some(None),
+            false => source
+                .containing(address)
+                .map(|target| Some(LoadedModule::new(&target))),
+        };
+        if let Some(target) = target {
+            exports.insert(key, CachedExport { address, target });
+        }
+        address
+    }
+
+    fn lookup<'a, S: ModuleSource + ?Sized>(
+        source: &'a S,
+        module: &Module<'a>,
+        key: &ExportKey,
+    ) -> *const c_void {
+        let resolved_export = match key {
+            ExportKey::Name(name) => module.resolve_export(source, name).ok(),
+            ExportKey::Ordinal(ordinal) => module
+                .resolve_export_by_ordinal(source, ordinal.clone())
+                .ok(),
+            ExportKey::Hash(hash, algorithm) => module
+                .image()
+                .export_by_hash(hash.clone(), algorithm.clone())
+                .and_then(|export| module.follow_forwarders(source, export.rva().clone()).ok()),
+        };
+        resolved_export.map_or(core::ptr::null(), |resolved_export| {
+            resolved_export.address()
+        })
+    }
+
+    fn module(self: &mut Self, module: &Module) -> &mut ModuleExports {
+        let loaded = LoadedModule::new(module);
+        let index = match self
+            .modules
+            .iter()
+            .position(|exports| exports.module.dll_base.eq(&loaded.dll_base))
+        {
+            Some(index) => index,
+            None => {
+                self.modules.push(ModuleExports::new(loaded));
+                self.modules.len() - 1
+            }
+        };
+        let exports = &mut self.modules[index];
+        if exports.module.ne(&loaded) {
+            *exports = ModuleExports::new(loaded);
+        }
+        exports
+    }
+
+    pub fn remove(self: &mut Self, module: &Module) {
+        let loaded = LoadedModule::new(module);
+        self.modules.retain(|exports| exports.module.ne(&loaded));
+    }
+
+    /// Drops the modules that are no longer in `source`, or were remapped
+    /// since they were cached, and the exports forwarded into such modules.
+    /// Each cached module is looked for in `source` once.
+    pub fn prune<S: ModuleSource + ?Sized>(self: &mut Self, source: &S) {
+        let mut loaded = BTreeMap::new();
+        let mut is_loaded =
+            |module: LoadedModule| *loaded.entry(module).or_insert_with(|| module.is_in(source));
+        self.modules.retain(|exports| is_loaded(exports.module));
+        for exports in &mut self.modules {
+            exports.retain(|export| export.target.is_none_or(&mut is_loaded));
+        }
+    }
+
+    pub fn clear(self: &mut Self) {
+        self.modules.clear();
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.modules.iter().map(|module| module.len()).sum()
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.len().eq(&0)
+    }
+}
+
+impl ModuleExports {
+    fn new(module: LoadedModule) -> Self {
+        Self {
+            module,
+            names: BTreeMap::new(),
+            ordinals: BTreeMap::new(),
+            hashes: BTreeMap::new(),
+        }
+    }
+
+    fn get(self: &Self, key: &ExportKey) -> Option<&CachedExport> {
+        match key {
+            ExportKey::Name(name) => self.names.get(*name),
+            ExportKey::Ordinal(ordinal) => self.ordinals.get(ordinal),
+            ExportKey::Hash(hash, algorithm) => self.hashes.get(&(hash.clone(), algorithm.clone())),
+        }
+    }
+
+    fn insert(self: &mut Self, key: ExportKey, export: CachedExport) {
+        match key {
+            ExportKey::Name(name) => self.names.insert(String::from(name), export),
+            ExportKey::Ordinal(ordinal) => self.ordinals.insert(ordinal, export),
+            ExportKey::Hash(hash, algorithm) => self.hashes.insert((hash, algorithm), export),
+        };
+    }
+
+    fn retain(self: &mut Self, mut keep: impl FnMut(&CachedExport) -> bool) {
+        self.names.retain(|_, export| keep(export));
+        self.ordinals.retain(|_, export| keep(export));
+        self.hashes.retain(|_, export| keep(export));
+    }
+
+    fn len(self: &Self) -> usize {
+        self.names.len() + self.ordinals.len() + self.hashes.len()
+    }
+}
+
+impl LoadedModule {
+    fn new(module: &Module) -> Self {
+        Self {
+            dll_base: module.dll_base(),
+            time_date_stamp: module.time_date_stamp(),
+        }
+    }
+
+    fn is_in<S: ModuleSource + ?Sized>(self: &Self, source: &S) -> bool {
+        source
+            .modules()
+            .any(|module| LoadedModule::new(&module).eq(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::ffi::c_void;
+
+    use super::{ExportCache, ExportKey};
+    use crate::windows::{test_image::TestImage, HashAlgorithm, Module, ModuleList};
+
+    fn dll_base(index: usize) -> *const c_void {
+        ((index + 1) * 0x100_0000) as *const c_void
+    }
+
+    /// `a.dll` exports `Local` and forwards `Run` to `b.dll`, which exports
+    /// it. The two were linked at different times.
+    fn images() -> [Vec<u8>; 2] {
+        [
+            TestImage::new("a.dll")
+                .time_date_stamp(0x6000_0000)
+                .export("Local", 0x2010)
+                .forward("Run", "b.Run")
+                .build(),
+            TestImage::new("b.dll")
+                .time_date_stamp(0x6100_0000)
+                .export("Run", 0x2020)
+                .build(),
+        ]
+    }
+
+    fn modules(images: &[Vec<u8>]) -> Vec<Module<'_>> {
+        ["a.dll", "b.dll"]
+            .iter()
+            .zip(images)
+            .enumerate()
+            .map(|(index, (name, bytes))| Module::from_file(name, dll_base(index), bytes).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn serves_hits_from_the_cache() {
+        let images = images();
+        let modules = modules(&images);
+        let list = ModuleList::new(&modules);
+        let mut cache = ExportCache::new();
+
+        let local = dll_base(0).wrapping_byte_add(0x2010);
+        assert_eq!(cache.export_function(&list, &modules[0], "Local"), local);
+        assert_eq!(cache.export_by_ordinal(&list, &modules[0], 1), local);
+        let hash = HashAlgorithm::Fnv1a64.hash_export_name("Local");
+        let by_hash = cache.export_by_hash(&list, &modules[0], hash, HashAlgorithm::Fnv1a64);
+        assert_eq!(by_hash, local);
+        assert_eq!(cache.len(), 3);
+
+        // A hit does not resolve again, so not even the modules are needed.
+        let empty = ModuleList::new(&[]);
+        let key = ExportKey::Name("Local");
+        assert_eq!(cache.resolve(&empty, &modules[0], key), local);
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn does_not_remember_misses() {
+        let images = images();
+        let modules = modules(&images);
+        let list = ModuleList::new(&modules);
+        let mut cache = ExportCache::new();
+
+        assert!(cache
+            .export_function(&list, &modules[0], "Missing")
+            .is_null());
+        assert!(cache.export_by_ordinal(&list, &modules[0], 3).is_null());
+        assert!(cache.is_empty());
+
+        // Nor forwarders whose target is not in the source.
+        let without_target = ModuleList::new(&modules[..1]);
+        assert!(cache
+            .export_function(&without_target, &modules[0], "Run")
+            .is_null());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn remembers_forwarded_exports() {
+        let images = images();
+        let modules = modules(&images);
+        let list = ModuleList::new(&modules);
+        let mut cache = ExportCache::new();
+
+        let run = dll_base(1).wrapping_byte_add(0x2020);
+        assert_eq!(cache.export_function(&list, &modules[0], "Run"), run);
+        assert_eq!(cache.len(), 1);
+        let without_target = ModuleList::new(&modules[..1]);
+        assert_eq!(
+            cache.export_function(&without_target, &modules[0], "Run"),
+            run
+        );
+
+        // Pruning against an unchanged source keeps everything.
+        cache.prune(&list);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn prunes_exports_forwarded_into_removed_modules() {
+        let images = images();
+        let modules = modules(&images);
+        let list = ModuleList::new(&modules);
+        let mut cache = ExportCache::new();
+
+        cache.export_function(&list, &modules[0], "Local");
+        cache.export_function(&list, &modules[0], "Run");
+        cache.export_function(&list, &modules[1], "Run");
+        assert_eq!(cache.len(), 3);
+
+        // b.dll is unloaded: its own exports and those forwarded into it go.
+        let without_target = ModuleList::new(&modules[..1]);
+        cache.prune(&without_target);
+        assert_eq!(cache.len(), 1);
+        assert!(cache
+            .export_function(&without_target, &modules[0], "Run")
+            .is_null());
+
+        cache.prune(&ModuleList::new(&[]));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn drops_modules_loaded_elsewhere() {
+        let images = images();
+        let modules = modules(&images);
+        let list = ModuleList::new(&modules);
+        let mut cache = ExportCache::new();
+
+        cache.export_function(&list, &modules[0], "Local");
+        cache.export_function(&list, &modules[1], "Run");
+
+        // a.dll reloaded at b.dll's old base replaces the entries there.
+        let reloaded = Module::from_file("a.dll", dll_base(1), &images[0]).unwrap();
+        let local = cache.export_function(&list, &reloaded, "Local");
+        assert_eq!(local, dll_base(1).wrapping_byte_add(0x2010));
+        assert_eq!(cache.len(), 2);
+
+        cache.remove(&modules[0]);
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+use core::{
+    ffi::c_void,
+    fmt::{self, Display, Formatter},
+};
+
+/// A forwarder string such as `NTDLL.RtlEnterCriticalSection` or
+/// `api-ms-win-core-synch-l1-2-0.#12`, split into the module and the export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Forwarder<'a> {
+    module: &'a str,
+    export: ForwardedExport<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForwardedExport<'a> {
+    Name(&'a str),
+    Ordinal(u32),
+}
+
+/// The forwarder strings followed while resolving an export, in order.
+#[derive(Debug, Clone, Copy)]
+pub struct ForwarderChain<'a> {
+    hops: [&'a str; ForwarderChain::MAX_DEPTH],
+    len: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvedExport<'a> {
+    address: *const c_void,
+    chain: ForwarderChain<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveFailure {
+    ExportNotFound,
+    ModuleNotLoaded,
+    MalformedForwarder,
+    Cycle,
+    TooDeep,
+}
+
+/// Why resolution failed and how far it got. Only the last forwarder is
+/// kept rather than the whole chain, which keeps the error small.
+#[derive(Debug, Clone, Copy)]
+pub struct ResolveError<'a> {
+    failure: ResolveFailure,
+    depth: usize,
+    last_hop: Option<&'a str>,
+}
+
+impl<'a> Forwarder<'a> {
+    pub fn parse(forwarder: &'a str) -> Option<Self> {
+        let (module, export) = forwarder.rsplit_once('.')?;
+        if module.is_empty() || export.is_empty() {
+            return None;
+        }
+        let export = match export.strip_prefix('#') {
+            Some(ordinal) => ForwardedExport::Ordinal(ordinal.parse().ok()?),
+            None => ForwardedExport::Name(export),
+        };
+        Some(Self { module, export })
+    }
+
+    /// The module name without its `.dll` extension.
+    pub fn module(self: &Self) -> &'a str {
+        self.module
+    }
+
+    pub fn export(self: &Self) -> &ForwardedExport<'a> {
+        &self.export
+    }
+}
+
+impl<'a> ForwarderChain<'a> {
+    pub const MAX_DEPTH: usize = 16;
+
+    pub fn new() -> Self {
+        Self {
+            hops: [""; ForwarderChain::MAX_DEPTH],
+            len: 0,
+        }
+    }
+
+    pub fn hops(self: &Self) -> &[&'a str] {
+        &self.hops[..self.len]
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.len.eq(&0)
+    }
+
+    pub fn contains(self: &Self, forwarder: &str) -> bool {
+        self.hops()
+            .iter()
+            .any(|hop| hop.eq_ignore_ascii_case(forwarder))
+    }
+
+    /// Returns `false` once the chain is `MAX_DEPTH` hops long.
+    pub fn push(self: &mut Self, forwarder: &'a str) -> bool {
+        if self.len.eq(&ForwarderChain::MAX_DEPTH) {
+            return false;
+        }
+        self.hops[self.len] = forwarder;
+        self.len += 1;
+        true
+    }
+}
+
+impl Default for ForwarderChain<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ResolvedExport<'a> {
+    pub fn new(address: *const c_void, chain: ForwarderChain<'a>) -> Self {
+        Self { address, chain }
+    }
+
+    pub fn address(self: &Self) -> *const c_void {
+        self.address
+    }
+
+    pub fn chain(self: &Self) -> &ForwarderChain<'a> {
+        &self.chain
+    }
+
+    pub fn is_forwarded(self: &Self) -> bool {
+        !self.chain.is_empty()
+    }
+}
+
+impl<'a> ResolveError<'a> {
+    pub fn new(failure: ResolveFailure, chain: &ForwarderChain<'a>) -> Self {
+        Self {
+            failure,
+            depth: chain.len(),
+            last_hop: chain.hops().last().cloned(),
+        }
+    }
+
+    pub fn failure(self: &Self) -> &ResolveFailure {
+        &self.failure
+    }
+
+    /// The number of forwarders followed before resolution failed.
+    pub fn depth(self: &Self) -> usize {
+        self.depth
+    }
+
+    /// The forwarder followed last before resolution failed.
+    pub fn last_hop(self: &Self) -> Option<&'a str> {
+        self.last_hop
+    }
+}
+
+impl Display for ForwarderChain<'_> {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, hop) in self.hops().iter().enumerate() {
+            if index.ne(&0) {
+                f.write_str(" -> ")?;
+            }
+            f.write_str(hop)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ResolveError<'_> {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        let failure = match self.failure {
+            ResolveFailure::ExportNotFound => "export not found",
+            ResolveFailure::ModuleNotLoaded => "forwarder target module is not loaded",
+            ResolveFailure::MalformedForwarder => "malformed forwarder",
+            ResolveFailure::Cycle => "forwarder cycle",
+            ResolveFailure::TooDeep => "too many forwarders",
+        };
+        f.write_str(failure)?;
+        if let Some(last_hop) = self.last_hop {
+            write!(f, " at {} after {} forwarders", last_hop, self.depth)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ResolveError<'_> {}
@@ -0,0 +1,227 @@
+/// Hash functions for looking modules and exports up without embedding
+/// their names. Every function is `const`, so hashes can be computed at
+/// compile time:
+///
+/// ```ignore
+/// const KERNEL32: u64 = HashAlgorithm::Fnv1a64.hash_module_name("kernel32.dll");
+/// const SLEEP: u64 = HashAlgorithm::Fnv1a64.hash_export_name("Sleep");
+/// ```
+///
+/// Module names are hashed as lowercased UTF-16LE, the way the loader stores
+/// them; export names are hashed as their exact bytes. 32-bit hashes are
+/// zero-extended to `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HashAlgorithm {
+    Fnv1a32,
+    Fnv1a64,
+    Crc32,
+    Djb2,
+}
+
+impl HashAlgorithm {
+    const CRC32_TABLE: [u32; 256] = HashAlgorithm::crc32_table();
+
+    const fn crc32_table() -> [u32; 256] {
+        let mut table = [0u32; 256];
+        let mut index = 0;
+        while index < 256 {
+            let mut value = index as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                value = match value & 1 {
+                    0 => value >> 1,
+                    _ => (value >> 1) ^ 0xedb88320,
+                };
+                bit += 1;
+            }
+            table[index] = value;
+            index += 1;
+        }
+        table
+    }
+
+    const fn initial(self: Self) -> u64 {
+        match self {
+            HashAlgorithm::Fnv1a32 => 0x811c9dc5,
+            HashAlgorithm::Fnv1a64 => 0xcbf29ce484222325,
+            HashAlgorithm::Crc32 => 0xffffffff,
+            HashAlgorithm::Djb2 => 5381,
+        }
+    }
+
+    const fn update(self: Self, state: u64, byte: u8) -> u64 {
+        match self {
+            HashAlgorithm::Fnv1a32 => {
+                ((state as u32 ^ byte as u32).wrapping_mul(0x01000193)) as u64
+            }
+            HashAlgorithm::Fnv1a64 => (state ^ byte as u64).wrapping_mul(0x100000001b3),
+            HashAlgorithm::Crc32 => {
+                let state = state as u32;
+                (HashAlgorithm::CRC32_TABLE[((state ^ byte as u32) & 0xff) as usize] ^ (state >> 8))
+                    as u64
+            }
+            HashAlgorithm::Djb2 => (state as u32).wrapping_mul(33).wrapping_add(byte as u32) as u64,
+        }
+    }
+
+    const fn finish(self: Self, state: u64) -> u64 {
+        match self {
+            HashAlgorithm::Crc32 => !(state as u32) as u64,
+            _ => state,
+        }
+    }
+
+    const fn update_utf16(self: Self, state: u64, unit: u16) -> u64 {
+        let unit = match unit {
+            0x41..=0x5a => unit + 0x20,
+            _ => unit,
+        };
+        let state = self.update(state, unit as u8);
+        self.update(state, (unit >> 8) as u8)
+    }
+
+    /// Hashes an export name byte for byte.
+    pub const fn hash_export_name(self: Self, name: &str) -> u64 {
+        let bytes = name.as_bytes();
+        let mut state = self.initial();
+        let mut index = 0;
+        while index < bytes.len() {
+            state = self.update(state, bytes[index]);
+            index += 1;
+        }
+        self.finish(state)
+    }
+
+    /// Hashes a module name such as `kernel32.dll` as lowercased UTF-16LE,
+    /// matching [`HashAlgorithm::hash_utf16_lowercase`] on the loader's copy.
+    pub const fn hash_module_name(self: Self, name: &str) -> u64 {
+        let bytes = name.as_bytes();
+        let mut state = self.initial();
+        let mut index = 0;
+        while index < bytes.len() {
+            // Decode one UTF-8 sequence; `str` guarantees it is well formed.
+            let (code_point, length) = match bytes[index] {
+                byte @ 0x00..=0x7f => (byte as u32, 1),
+                byte @ 0xc0..=0xdf => (
+                    ((byte as u32 & 0x1f) << 6) | (bytes[index + 1] as u32 & 0x3f),
+                    2,
+                ),
+                byte @ 0xe0..=0xef => (
+                    ((byte as u32 & 0x0f) << 12)
+                        | ((bytes[index + 1] as u32 & 0x3f) << 6)
+                        | (bytes[index + 2] as u32 & 0x3f),
+                    3,
+                ),
+                byte => (
+                    ((byte as u32 & 0x07) << 18)
+                        | ((bytes[index + 1] as u32 & 0x3f) << 12)
+                        | ((bytes[index + 2] as u32 & 0x3f) << 6)
+                        | (bytes[index + 3] as u32 & 0x3f),
+                    4,
+                ),
+            };
+            state = match code_point {
+                0x10000.. => {
+                    let code_point = code_point - 0x10000;
+                    let state = self.update_utf16(state, 0xd800 | (code_point >> 10) as u16);
+                    self.update_utf16(state, 0xdc00 | (code_point & 0x3ff) as u16)
+                }
+                _ => self.update_utf16(state, code_point as u16),
+            };
+            index += length;
+        }
+        self.finish(state)
+    }
+
+    /// Hashes UTF-16 units, lowercasing ASCII letters first.
+    pub const fn hash_utf16_lowercase(self: Self, units: &[u16]) -> u64 {
+        let mut state = self.initial();
+        let mut index = 0;
+        while index < units.len() {
+            state = self.update_utf16(state, units[index]);
+            index += 1;
+        }
+        self.finish(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::HashAlgorithm;
+
+    // Published FNV-1a, CRC-32 (IEEE) and djb2 vectors.
+    const VECTORS: [(&str, [u64; 4]); 4] = [
+        ("", [0x811c9dc5, 0xcbf29ce484222325, 0x00000000, 0x1505]),
+        ("a", [0xe40c292c, 0xaf63dc4c8601ec8c, 0xe8b7be43, 0x2b606]),
+        (
+            "foobar",
+            [0xbf9cf968, 0x85944171f73967e8, 0x9ef61f95, 0xfde460be],
+        ),
+        (
+            "123456789",
+            [0xbb86b11c, 0x06d5573923c6cdfc, 0xcbf43926, 0x35cdbb82],
+        ),
+    ];
+
+    const ALGORITHMS: [HashAlgorithm; 4] = [
+        HashAlgorithm::Fnv1a32,
+        HashAlgorithm::Fnv1a64,
+        HashAlgorithm::Crc32,
+        HashAlgorithm::Djb2,
+    ];
+
+    #[test]
+    fn export_names_match_known_vectors() {
+        for (name, hashes) in VECTORS {
+            for (algorithm, hash) in ALGORITHMS.iter().zip(hashes) {
+                assert_eq!(
+                    algorithm.hash_export_name(name),
+                    hash,
+                    "{:?} of {:?}",
+                    algorithm,
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn module_names_hash_as_lowercase_utf16() {
+        // The same functions over the UTF-16LE bytes of `kernel32.dll`.
+        let hashes = [0xc705800d, 0x7f1bf8b449d16c2d, 0x2eca438c, 0x3bbc195];
+        let units: Vec<u16> = "kernel32.dll".encode_utf16().collect();
+        for (algorithm, hash) in ALGORITHMS.iter().zip(hashes) {
+            assert_eq!(algorithm.hash_module_name("kernel32.dll"), hash);
+            assert_eq!(algorithm.hash_module_name("KERNEL32.DLL"), hash);
+            assert_eq!(algorithm.hash_utf16_lowercase(&units), hash);
+        }
+    }
+
+    #[test]
+    fn module_names_decode_every_utf8_length() {
+        // Only ASCII is lowercased, the way the loader's copy is compared.
+        let name = "Ä€\u{1f600}x.dll";
+        let units: Vec<u16> = name.encode_utf16().collect();
+        assert_eq!(units.len(), 9);
+        for algorithm in ALGORITHMS {
+            assert_eq!(
+                algorithm.hash_module_name(name),
+                algorithm.hash_utf16_lowercase(&units)
+            );
+            assert_ne!(
+                algorithm.hash_module_name(name),
+                algorithm.hash_module_name("ä€\u{1f600}x.dll")
+            );
+        }
+    }
+
+    #[test]
+    fn hashes_are_const() {
+        const SLEEP: u64 = HashAlgorithm::Fnv1a64.hash_export_name("Sleep");
+        const KERNEL32: u64 = HashAlgorithm::Crc32.hash_module_name("kernel32.dll");
+        assert_eq!(SLEEP, 0x503cbccd6a5cdea8);
+        assert_eq!(KERNEL32, 0x2eca438c);
+    }
+}
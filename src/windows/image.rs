@@ -0,0 +1,936 @@
+#[cfg(any(feature = "alloc", test))]
+use alloc::vec::Vec;
+use core::{
+    ffi::{c_void, CStr},
+    marker::PhantomData,
+    mem::{align_of, size_of, zeroed},
+    ptr::copy_nonoverlapping,
+    slice::from_raw_parts,
+};
+
+#[cfg(any(feature = "alloc", test))]
+use super::PatternSet;
+use super::{
+    Export, ExportIterator, HashAlgorithm, ImageArm64RuntimeFunction, ImageDataDirectory,
+    ImageDebugDirectory, ImageDirectoryEntry, ImageDosHeader, ImageExportDirectory,
+    ImageFileHeader, ImageLoadConfigDirectory, ImageMachine, ImageNtHeaders, ImageOptionalHeader,
+    ImageOptionalHeader32, ImageOptionalHeader64, ImageSectionHeaders, Pattern, RichHeader, Rtti,
+    SecurityReport,
+};
+
+/// Integers that any bit pattern is valid for, read unaligned out of images.
+pub(crate) trait Integer: Copy {}
+
+impl Integer for u16 {}
+impl Integer for u32 {}
+impl Integer for u64 {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageLayout {
+    Mapped,
+    File,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Image<'a> {
+    base: *const c_void,
+    size: usize,
+    layout: ImageLayout,
+    marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Image<'a> {
+    pub fn from_file(bytes: &'a [u8]) -> Option<Self> {
+        Image::from_bytes(bytes, ImageLayout::File)
+    }
+
+    pub fn from_mapped(bytes: &'a [u8]) -> Option<Self> {
+        Image::from_bytes(bytes, ImageLayout::Mapped)
+    }
+
+    /// # Safety
+    ///
+    /// `dll_base` must point to an image mapped by the loader that stays
+    /// mapped for `'a`.
+    pub unsafe fn from_base(dll_base: *const c_void) -> Self {
+        let dos_header = &*(dll_base as *const ImageDosHeader);
+        let size_of_image = dos_header.nt_headers().optional_header().size_of_image();
+        Self {
+            base: dll_base,
+            size: size_of_image.clone() as usize,
+            layout: ImageLayout::Mapped,
+            marker: PhantomData,
+        }
+    }
+
+    fn from_bytes(bytes: &'a [u8], layout: ImageLayout) -> Option<Self> {
+        let image = Self {
+            base: bytes.as_ptr() as *const c_void,
+            size: bytes.len(),
+            layout,
+            marker: PhantomData,
+        };
+        image.validate().map(|_| image)
+    }
+
+    fn validate(self: &Self) -> Option<()> {
+        let dos_header = self.at_offset::<ImageDosHeader>(0)?;
+        if dos_header.magic().ne(&ImageDosHeader::MAGIC) || dos_header.lfanew().lt(&0) {
+            return None;
+        }
+
+        let nt_headers_offset = dos_header.lfanew().clone() as usize;
+        let nt_headers = self.at_offset::<ImageNtHeaders>(nt_headers_offset)?;
+        if nt_headers.signature().ne(&ImageNtHeaders::SIGNATURE) {
+            return None;
+        }
+
+        let file_header = nt_headers.file_header();
+        let optional_header_offset =
+            nt_headers_offset.checked_add(ImageNtHeaders::OPTIONAL_HEADER_OFFSET)?;
+        let optional_header_size = match self.read_at_offset::<u16>(optional_header_offset)? {
+            ImageOptionalHeader32::MAGIC => size_of::<ImageOptionalHeader32>(),
+            ImageOptionalHeader64::MAGIC => size_of::<ImageOptionalHeader64>(),
+            _ => return None,
+        };
+        self.slice_at_offset(optional_header_offset, optional_header_size)?;
+
+        let section_headers_offset = optional_header_offset
+            .checked_add(file_header.size_of_optional_header().clone() as usize)?;
+        self.array_at_offset::<ImageSectionHeaders>(
+            section_headers_offset,
+            file_header.number_of_sections().clone() as usize,
+        )
+        .map(|_| ())
+    }
+
+    pub fn base(self: &Self) -> *const c_void {
+        self.base
+    }
+
+    pub fn size(self: &Self) -> &usize {
+        &self.size
+    }
+
+    pub fn layout(self: &Self) -> &ImageLayout {
+        &self.layout
+    }
+
+    pub fn bytes(self: &Self) -> &'a [u8] {
+        unsafe { from_raw_parts(self.base as *const u8, self.size) }
+    }
+
+    pub fn dos_header(self: &Self) -> &'a ImageDosHeader {
+        unsafe { &*(self.base as *const ImageDosHeader) }
+    }
+
+    pub fn nt_headers(self: &Self) -> &'a ImageNtHeaders {
+        self.dos_header().nt_headers()
+    }
+
+    pub fn file_header(self: &Self) -> &'a ImageFileHeader {
+        self.nt_headers().file_header()
+    }
+
+    pub fn optional_header(self: &Self) -> ImageOptionalHeader<'a> {
+        self.nt_headers().optional_header()
+    }
+
+    pub fn section_headers(self: &Self) -> &'a [ImageSectionHeaders] {
+        self.nt_headers().section_headers()
+    }
+
+    pub fn rich_header(self: &Self) -> Option<RichHeader<'a>> {
+        self.dos_header().rich_header()
+    }
+
+    pub fn dos_stub(self: &Self) -> &'a [u8] {
+        self.dos_header().dos_stub()
+    }
+
+    /// File offset where data appended after the last section begins, or
+    /// `None` for mapped images and files without an overlay.
+    pub fn overlay_offset(self: &Self) -> Option<usize> {
+        if self.layout.ne(&ImageLayout::File) {
+            return None;
+        }
+
+        let end_of_sections = self
+            .section_headers()
+            .iter()
+            .filter(|section| section.size_of_raw_data().ne(&0))
+            .map(|section| {
+                section.pointer_to_raw_data().clone() as usize
+                    + section.size_of_raw_data().clone() as usize
+            })
+            .max()
+            .unwrap_or(0)
+            .max(self.optional_header().size_of_headers().clone() as usize);
+
+        let end = self.overlay_end(end_of_sections);
+        end_of_sections.lt(&end).then_some(end_of_sections)
+    }
+
+    /// Data appended after the last section of a file image, excluding the
+    /// certificate table.
+    pub fn overlay(self: &Self) -> Option<&'a [u8]> {
+        let offset = self.overlay_offset()?;
+        self.bytes().get(offset..self.overlay_end(offset))
+    }
+
+    fn overlay_end(self: &Self, overlay_offset: usize) -> usize {
+        // The security directory holds a file offset rather than an RVA.
+        let certificate_table = self.data_directory(ImageDirectoryEntry::Security);
+        let certificate_table_offset = certificate_table.virtual_address().clone() as usize;
+        match certificate_table.is_present()
+            && certificate_table_offset.ge(&overlay_offset)
+            && certificate_table_offset.lt(&self.size)
+        {
+            true => certificate_table_offset,
+            false => self.size,
+        }
+    }
+
+    pub fn data_directory(self: &Self, directory: ImageDirectoryEntry) -> &'a ImageDataDirectory {
+        self.optional_header().data_directory(directory)
+    }
+
+    /// Translates an RVA into an offset within [`Image::bytes`], following
+    /// the section table when the image is laid out as on disk.
+    pub fn offset_of_rva(self: &Self, rva: u32) -> Option<usize> {
+        let offset = match self.layout {
+            ImageLayout::Mapped => rva,
+            ImageLayout::File => self.rva_to_offset(rva)?,
+        } as usize;
+        offset.lt(&self.size).then_some(offset)
+    }
+
+    pub fn rva_to_offset(self: &Self, rva: u32) -> Option<u32> {
+        match rva.lt(&self.optional_header().size_of_headers()) {
+            true => Some(rva),
+            false => self.section_containing_rva(rva)?.rva_to_offset(rva),
+        }
+    }
+
+    pub fn offset_to_rva(self: &Self, offset: u32) -> Option<u32> {
+        match offset.lt(&self.optional_header().size_of_headers()) {
+            true => Some(offset),
+            false => self
+                .section_containing_offset(offset)?
+                .offset_to_rva(offset),
+        }
+    }
+
+    pub fn section_containing_rva(self: &Self, rva: u32) -> Option<&'a ImageSectionHeaders> {
+        self.section_headers()
+            .iter()
+            .find(|section| section.contains_rva(rva))
+    }
+
+    pub fn section_containing_offset(self: &Self, offset: u32) -> Option<&'a ImageSectionHeaders> {
+        self.section_headers()
+            .iter()
+            .find(|section| section.contains_offset(offset))
+    }
+
+    pub fn section_by_name(self: &Self, name: &str) -> Option<&'a ImageSectionHeaders> {
+        self.section_headers().iter().find(|section| {
+            self.section_name(section)
+                .is_some_and(|section_name| section_name.eq(name.as_bytes()))
+        })
+    }
+
+    pub fn code_section(self: &Self) -> Option<&'a [u8]> {
+        self.section_data(ImageSectionHeaders::is_code_section)
+    }
+
+    pub fn vmt_section(self: &Self) -> Option<&'a [u8]> {
+        self.section_data(ImageSectionHeaders::is_vmt_section)
+    }
+
+    pub fn data_section(self: &Self) -> Option<&'a [u8]> {
+        self.section_data(ImageSectionHeaders::is_data_section)
+    }
+
+    fn section_data(self: &Self, predicate: fn(&ImageSectionHeaders) -> bool) -> Option<&'a [u8]> {
+        self.section_headers()
+            .iter()
+            .find(|section| predicate(section))?
+            .data(self)
+    }
+
+    /// RVA of the first match of `pattern` in any section.
+    pub fn find_pattern(self: &Self, pattern: &Pattern) -> Option<u32> {
+        self.find_all_pattern(pattern).next()
+    }
+
+    pub fn find_all_pattern<'p>(
+        self: &Self,
+        pattern: &'p Pattern,
+    ) -> impl Iterator<Item = u32> + use<'a, 'p> {
+        let image = self.clone();
+        self.section_headers()
+            .iter()
+            .flat_map(move |section| image.find_all_pattern_in(section, pattern))
+    }
+
+    pub fn find_pattern_in(
+        self: &Self,
+        section: &ImageSectionHeaders,
+        pattern: &Pattern,
+    ) -> Option<u32> {
+        self.find_all_pattern_in(section, pattern).next()
+    }
+
+    pub fn find_all_pattern_in<'p>(
+        self: &Self,
+        section: &ImageSectionHeaders,
+        pattern: &'p Pattern,
+    ) -> impl Iterator<Item = u32> + use<'a, 'p> {
+        let virtual_address = section.virtual_address().clone();
+        pattern
+            .find_iter(section.data(self).unwrap_or(&[]))
+            .map_while(move |offset| virtual_address.checked_add(u32::try_from(offset).ok()?))
+    }
+
+    /// First RVA of every pattern in `set`, scanning each section once.
+    #[cfg(any(feature = "alloc", test))]
+    pub fn find_patterns(self: &Self, set: &PatternSet) -> Vec<Option<u32>> {
+        let mut found = Vec::new();
+        found.resize(set.patterns().len(), None);
+        for section in self.section_headers() {
+            let section_found = self.find_patterns_in(section, set);
+            for (rva, section_rva) in found.iter_mut().zip(section_found) {
+                if rva.is_none() {
+                    *rva = section_rva;
+                }
+            }
+        }
+        found
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub fn find_patterns_in(
+        self: &Self,
+        section: &ImageSectionHeaders,
+        set: &PatternSet,
+    ) -> Vec<Option<u32>> {
+        let virtual_address = section.virtual_address().clone();
+        set.find_first(section.data(self).unwrap_or(&[]))
+            .into_iter()
+            .map(|offset| {
+                offset.and_then(|offset| virtual_address.checked_add(u32::try_from(offset).ok()?))
+            })
+            .collect()
+    }
+
+    /// Absolute address of an RVA: relative to the actual base for mapped
+    /// images and to the preferred `image_base` for file images.
+    pub fn rva_to_address(self: &Self, rva: u32) -> Option<u64> {
+        if rva.ge(&self.optional_header().size_of_image()) {
+            return None;
+        }
+        let base = match self.layout {
+            ImageLayout::Mapped => self.base as u64,
+            ImageLayout::File => self.optional_header().image_base(),
+        };
+        base.checked_add(rva as u64)
+    }
+
+    pub fn address_to_rva(self: &Self, address: u64) -> Option<u32> {
+        let base = match self.layout {
+            ImageLayout::Mapped => self.base as u64,
+            ImageLayout::File => self.optional_header().image_base(),
+        };
+        let rva = u32::try_from(address.checked_sub(base)?).ok()?;
+        rva.lt(&self.optional_header().size_of_image())
+            .then_some(rva)
+    }
+
+    /// Resolves the target of a RIP-relative operand such as the `disp32` of
+    /// `mov rax, [rip + disp32]` or `lea rcx, [rip + disp32]`, given the RVA
+    /// of the instruction, the offset of the displacement within it and the
+    /// full instruction length.
+    pub fn resolve_rip_relative(
+        self: &Self,
+        rva: u32,
+        operand_offset: u32,
+        instruction_length: u32,
+    ) -> Option<u32> {
+        let displacement = self.slice_at_rva(rva.checked_add(operand_offset)?, size_of::<i32>())?;
+        let displacement = i32::from_le_bytes(displacement.try_into().ok()?);
+        self.relative_target(rva, instruction_length, displacement)
+    }
+
+    /// Resolves the target of a relative `call rel32` (`E8`), `jmp rel32`
+    /// (`E9`), `jmp rel8` (`EB`) or conditional jump at `rva`.
+    pub fn resolve_relative_branch(self: &Self, rva: u32) -> Option<u32> {
+        let opcode = self.slice_at_rva(rva, 2)?;
+        match opcode {
+            [0xe8 | 0xe9, ..] => self.resolve_rip_relative(rva, 1, 5),
+            [0x0f, 0x80..=0x8f] => self.resolve_rip_relative(rva, 2, 6),
+            [0xeb | 0x70..=0x7f, displacement] => {
+                self.relative_target(rva, 2, displacement.clone() as i8 as i32)
+            }
+            _ => None,
+        }
+    }
+
+    fn relative_target(
+        self: &Self,
+        rva: u32,
+        instruction_length: u32,
+        displacement: i32,
+    ) -> Option<u32> {
+        let next_instruction = rva.checked_add(instruction_length)?;
+        let target = next_instruction.checked_add_signed(displacement)?;
+        target
+            .lt(&self.optional_header().size_of_image())
+            .then_some(target)
+    }
+
+    /// Resolves `/123` style long names through the COFF string table, which
+    /// is only present in file images; other names are returned as stored.
+    pub fn section_name(self: &Self, section: &'a ImageSectionHeaders) -> Option<&'a [u8]> {
+        let short_name = section.short_name();
+        let string_table_offset = match short_name {
+            [b'/', b'/', encoded @ ..] => encoded.iter().try_fold(0usize, |offset, byte| {
+                let digit = match byte {
+                    b'A'..=b'Z' => byte - b'A',
+                    b'a'..=b'z' => byte - b'a' + 26,
+                    b'0'..=b'9' => byte - b'0' + 52,
+                    b'+' => 62,
+                    b'/' => 63,
+                    _ => return None,
+                };
+                offset.checked_mul(64)?.checked_add(digit as usize)
+            })?,
+            [b'/', decimal @ ..] => core::str::from_utf8(decimal).ok()?.parse().ok()?,
+            _ => return Some(short_name),
+        };
+
+        let string_table = self.string_table()?;
+        let name = string_table.get(string_table_offset..)?;
+        let length = name.iter().position(|byte| byte.eq(&0))?;
+        Some(&name[..length])
+    }
+
+    /// The COFF string table that follows the symbol table in file images.
+    pub fn string_table(self: &Self) -> Option<&'a [u8]> {
+        if self.layout.ne(&ImageLayout::File) {
+            return None;
+        }
+
+        const SIZE_OF_SYMBOL: usize = 18;
+        let file_header = self.file_header();
+        let offset = match file_header.pointer_to_symbol_table() {
+            0 => return None,
+            pointer_to_symbol_table => (file_header.number_of_symbols().clone() as usize)
+                .checked_mul(SIZE_OF_SYMBOL)?
+                .checked_add(pointer_to_symbol_table.clone() as usize)?,
+        };
+        let size = u32::from_le_bytes(
+            self.slice_at_offset(offset, size_of::<u32>())?
+                .try_into()
+                .ok()?,
+        );
+        self.slice_at_offset(offset, size as usize)
+    }
+
+    /// The NUL-terminated UTF-8 string at `rva`.
+    pub fn str_at_rva(self: &Self, rva: u32) -> Option<&'a str> {
+        let bytes = self.bytes().get(self.offset_of_rva(rva)?..)?;
+        CStr::from_bytes_until_nul(bytes).ok()?.to_str().ok()
+    }
+
+    pub fn slice_at_rva(self: &Self, rva: u32, size: usize) -> Option<&'a [u8]> {
+        self.slice_at_offset(self.offset_of_rva(rva)?, size)
+    }
+
+    pub fn at_rva<T>(self: &Self, rva: u32) -> Option<&'a T> {
+        self.at_offset(self.offset_of_rva(rva)?)
+    }
+
+    pub fn array_at_rva<T>(self: &Self, rva: u32, count: usize) -> Option<&'a [T]> {
+        self.array_at_offset(self.offset_of_rva(rva)?, count)
+    }
+
+    fn slice_at_offset(self: &Self, offset: usize, size: usize) -> Option<&'a [u8]> {
+        self.bytes().get(offset..offset.checked_add(size)?)
+    }
+
+    fn at_offset<T>(self: &Self, offset: usize) -> Option<&'a T> {
+        self.array_at_offset(offset, 1).map(|array| &array[0])
+    }
+
+    /// File buffers carry no alignment guarantee, so only the packed image
+    /// structures and byte arrays can be referenced in place; wider values
+    /// are read with [`Image::read_at_rva`].
+    fn array_at_offset<T>(self: &Self, offset: usize, count: usize) -> Option<&'a [T]> {
+        const { assert!(align_of::<T>() == 1) };
+        let bytes = self.slice_at_offset(offset, count.checked_mul(size_of::<T>())?)?;
+        Some(unsafe { from_raw_parts(bytes.as_ptr() as *const T, count) })
+    }
+
+    /// Copies the integer at `rva` out of the image.
+    pub(crate) fn read_at_rva<T: Integer>(self: &Self, rva: u32) -> Option<T> {
+        self.read_at_offset(self.offset_of_rva(rva)?)
+    }
+
+    fn read_at_offset<T: Integer>(self: &Self, offset: usize) -> Option<T> {
+        let bytes = self.slice_at_offset(offset, size_of::<T>())?;
+        Some(unsafe { (bytes.as_ptr() as *const T).read_unaligned() })
+    }
+
+    pub fn load_config_directory(self: &Self) -> Option<ImageLoadConfigDirectory> {
+        let data_directory = self.data_directory(ImageDirectoryEntry::LoadConfig);
+        if !data_directory.is_present() {
+            return None;
+        }
+
+        let offset = self.offset_of_rva(data_directory.virtual_address().clone())?;
+        let size_bytes = self.slice_at_offset(offset, size_of::<u32>())?;
+        let size = u32::from_le_bytes(size_bytes.try_into().ok()?) as usize;
+        Some(match self.optional_header() {
+            ImageOptionalHeader::Pe32(_) => {
+                ImageLoadConfigDirectory::Pe32(self.copy_at_offset(offset, size)?)
+            }
+            ImageOptionalHeader::Pe64(_) => {
+                ImageLoadConfigDirectory::Pe64(self.copy_at_offset(offset, size)?)
+            }
+        })
+    }
+
+    /// Copies the first `size` bytes at `offset` into a zeroed `T`, for
+    /// structures that grew over time and record their own size.
+    fn copy_at_offset<T: Copy>(self: &Self, offset: usize, size: usize) -> Option<T> {
+        let bytes = self.slice_at_offset(offset, size.min(size_of::<T>()))?;
+        let mut value: T = unsafe { zeroed() };
+        unsafe {
+            copy_nonoverlapping(bytes.as_ptr(), &mut value as *mut T as *mut u8, bytes.len())
+        };
+        Some(value)
+    }
+
+    pub fn debug_directories(self: &Self) -> &'a [ImageDebugDirectory] {
+        let data_directory = self.data_directory(ImageDirectoryEntry::Debug);
+        if !data_directory.is_present() {
+            return &[];
+        }
+
+        let count = data_directory.size().clone() as usize / size_of::<ImageDebugDirectory>();
+        self.array_at_rva(data_directory.virtual_address().clone(), count)
+            .unwrap_or(&[])
+    }
+
+    /// The ARM64 `.pdata` entries, sorted by `begin_address`.
+    pub fn arm64_runtime_functions(self: &Self) -> &'a [ImageArm64RuntimeFunction] {
+        let data_directory = self.data_directory(ImageDirectoryEntry::Exception);
+        if !data_directory.is_present() || !self.file_header().is_machine(ImageMachine::Arm64) {
+            return &[];
+        }
+
+        let count = data_directory.size().clone() as usize / size_of::<ImageArm64RuntimeFunction>();
+        self.array_at_rva(data_directory.virtual_address().clone(), count)
+            .unwrap_or(&[])
+    }
+
+    pub fn arm64_function_length(
+        self: &Self,
+        runtime_function: &ImageArm64RuntimeFunction,
+    ) -> Option<u32> {
+        match runtime_function.packed() {
+            Some(packed) => Some(packed.function_length().clone()),
+            None => self
+                .read_at_rva::<u32>(runtime_function.xdata_rva()?)
+                .map(ImageArm64RuntimeFunction::xdata_function_length),
+        }
+    }
+
+    /// The `.pdata` entry of the ARM64 function containing `rva`.
+    pub fn arm64_runtime_function(self: &Self, rva: u32) -> Option<&'a ImageArm64RuntimeFunction> {
+        let runtime_functions = self.arm64_runtime_functions();
+        let index = runtime_functions
+            .partition_point(|runtime_function| runtime_function.begin_address().le(&rva))
+            .checked_sub(1)?;
+        let runtime_function = &runtime_functions[index];
+        let length = self.arm64_function_length(runtime_function)?;
+        (rva - runtime_function.begin_address())
+            .lt(&length)
+            .then_some(runtime_function)
+    }
+
+    pub fn debug_data(self: &Self, debug_directory: &ImageDebugDirectory) -> Option<&'a [u8]> {
+        let size = debug_directory.size_of_data().clone() as usize;
+        match self.layout {
+            ImageLayout::Mapped => match debug_directory.address_of_raw_data() {
+                0 => None,
+                rva => self.slice_at_rva(rva.clone(), size),
+            },
+            ImageLayout::File => {
+                let offset = debug_directory.pointer_to_raw_data().clone() as usize;
+                self.slice_at_offset(offset, size)
+            }
+        }
+    }
+
+    pub fn export_directory(self: &Self) -> Option<&'a ImageExportDirectory> {
+        let data_directory = self.data_directory(ImageDirectoryEntry::Export);
+        if !data_directory.is_present() {
+            return None;
+        }
+        self.at_rva(data_directory.virtual_address().clone())
+    }
+
+    pub fn exports(self: &Self) -> ExportIterator<'a> {
+        ExportIterator::new(self)
+    }
+
+    pub fn export(self: &Self, name: &str) -> Option<Export<'a>> {
+        self.exports().find(|export| export.name().eq(name))
+    }
+
+    /// Biased ordinal and RVA of every used slot of the export address
+    /// table, named or not.
+    pub fn export_functions(self: &Self) -> impl Iterator<Item = (u32, u32)> + use<'a> {
+        let export_directory = self.export_directory();
+        let base = export_directory.map_or(0, |export_directory| export_directory.base());
+        export_directory
+            .and_then(|export_directory| {
+                self.array_at_rva::<[u8; 4]>(
+                    export_directory.address_of_functions_rva().clone(),
+                    export_directory.number_of_functions().clone() as usize,
+                )
+            })
+            .unwrap_or(&[])
+            .iter()
+            .enumerate()
+            .map(move |(index, rva)| {
+                let ordinal = base.wrapping_add(index as u32);
+                (ordinal, u32::from_le_bytes(rva.clone()))
+            })
+            .filter(|(_, rva)| rva.ne(&0))
+    }
+
+    /// RVA of the export with the biased `ordinal`, named or not.
+    pub fn export_rva_by_ordinal(self: &Self, ordinal: u32) -> Option<u32> {
+        let export_directory = self.export_directory()?;
+        // Ordinals wrap around past `u32::MAX` like those of the iterators.
+        let index = ordinal.wrapping_sub(export_directory.base().clone());
+        let functions = self.array_at_rva::<[u8; 4]>(
+            export_directory.address_of_functions_rva().clone(),
+            export_directory.number_of_functions().clone() as usize,
+        )?;
+        let rva = u32::from_le_bytes(functions.get(index as usize)?.clone());
+        rva.ne(&0).then_some(rva)
+    }
+
+    /// Looks an export up by the hash of its name, see [`HashAlgorithm`].
+    pub fn export_by_hash(self: &Self, hash: u64, algorithm: HashAlgorithm) -> Option<Export<'a>> {
+        self.exports()
+            .find(|export| algorithm.hash_export_name(export.name()).eq(&hash))
+    }
+
+    /// Looks an export up by its undecorated C++ name, either qualified
+    /// (`Bar::Foo`) or as the full signature the demangler prints.
+    #[cfg(any(feature = "demangle", test))]
+    pub fn export_by_undecorated_name(self: &Self, name: &str) -> Option<Export<'a>> {
+        self.exports()
+            .find(|export| export.has_undecorated_name(name))
+    }
+
+    pub fn rtti(self: &Self) -> Rtti<'a> {
+        Rtti::new(self.clone())
+    }
+
+    pub fn security_report(self: &Self) -> SecurityReport<'a> {
+        SecurityReport::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{Image, ImageDirectoryEntry, ImageMachine};
+    use crate::windows::test_image::TestImage;
+
+    /// A file image whose only section is named `name`, with a COFF string
+    /// table holding `.debug_info` at offset 4 and `.gnu_debuglink` after it.
+    fn image_with_section_name(name: &[u8]) -> Vec<u8> {
+        TestImage::new("sample.dll")
+            .section_name(name)
+            .string_table(b".debug_info\0.gnu_debuglink\0")
+            .build()
+    }
+
+    fn section_name(bytes: &[u8]) -> Option<&[u8]> {
+        let image = Image::from_file(bytes).unwrap();
+        image.section_name(&image.section_headers()[0])
+    }
+
+    #[test]
+    fn short_section_names() {
+        let bytes = image_with_section_name(b".text");
+        assert_eq!(section_name(&bytes), Some(&b".text"[..]));
+        let bytes = image_with_section_name(b".textbss");
+        assert_eq!(section_name(&bytes), Some(&b".textbss"[..]));
+    }
+
+    #[test]
+    fn decimal_string_table_offsets() {
+        let bytes = image_with_section_name(b"/4");
+        assert_eq!(section_name(&bytes), Some(&b".debug_info"[..]));
+        let bytes = image_with_section_name(b"/16");
+        assert_eq!(section_name(&bytes), Some(&b".gnu_debuglink"[..]));
+        let bytes = image_with_section_name(b"/4096");
+        assert_eq!(section_name(&bytes), None);
+        let bytes = image_with_section_name(b"/x");
+        assert_eq!(section_name(&bytes), None);
+    }
+
+    #[test]
+    fn base64_string_table_offsets() {
+        let bytes = image_with_section_name(b"//AAAAAE");
+        assert_eq!(section_name(&bytes), Some(&b".debug_info"[..]));
+        let bytes = image_with_section_name(b"//Q");
+        assert_eq!(section_name(&bytes), Some(&b".gnu_debuglink"[..]));
+        let bytes = image_with_section_name(b"//////");
+        assert_eq!(section_name(&bytes), None);
+        let bytes = image_with_section_name(b"//A-");
+        assert_eq!(section_name(&bytes), None);
+    }
+
+    #[test]
+    fn mapped_images_have_no_string_table() {
+        let bytes = image_with_section_name(b"/4");
+        let image = Image::from_mapped(&bytes).unwrap();
+        assert_eq!(image.section_name(&image.section_headers()[0]), None);
+    }
+
+    /// An ARM64 image with a packed 92-byte function at 0x2000 and one at
+    /// 0x2100 whose 64-byte length is in its `.xdata` record.
+    /// The section is stored at file offset 0x400, so file images have to
+    /// translate RVAs through the section table.
+    fn arm64_image(machine: ImageMachine) -> TestImage {
+        let pdata: Vec<u8> = [0x2000u32, 0x01e3005d, 0x2100, 0x1900]
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        TestImage::new("sample.dll")
+            .machine(machine)
+            .contents(0x1800, &pdata)
+            .contents(0x1900, &0x0880_0010u32.to_le_bytes())
+            .data_directory(ImageDirectoryEntry::Exception, 0x1800, 16)
+            .raw_data(0x400, 0x2000)
+    }
+
+    #[test]
+    fn arm64_runtime_functions_cover_their_functions() {
+        let test_image = arm64_image(ImageMachine::Arm64);
+        let file = test_image.build();
+        let mapped = test_image.build_mapped();
+        for image in [Image::from_file(&file), Image::from_mapped(&mapped)] {
+            let image = image.unwrap();
+            assert_eq!(image.section_headers().len(), 1);
+            assert_eq!(image.rva_to_offset(0x1800), Some(0xc00));
+            assert_eq!(image.arm64_runtime_functions().len(), 2);
+
+            let begin = |rva| {
+                image
+                    .arm64_runtime_function(rva)
+                    .map(|runtime_function| runtime_function.begin_address())
+            };
+            assert_eq!(begin(0x1fff), None);
+            assert_eq!(begin(0x2000), Some(0x2000));
+            assert_eq!(begin(0x205b), Some(0x2000));
+            assert_eq!(begin(0x205c), None);
+            assert_eq!(begin(0x2100), Some(0x2100));
+            assert_eq!(begin(0x213f), Some(0x2100));
+            assert_eq!(begin(0x2140), None);
+
+            let packed = image.arm64_runtime_functions()[0].packed().unwrap();
+            assert_eq!(packed.function_length(), &92);
+            assert_eq!(packed.saved_integer_registers(), &3);
+            assert_eq!(packed.frame_size(), &48);
+            assert!(image.arm64_runtime_functions()[1].packed().is_none());
+        }
+    }
+
+    #[test]
+    fn other_machines_have_no_arm64_runtime_functions() {
+        let bytes = arm64_image(ImageMachine::Amd64).build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert!(image.arm64_runtime_functions().is_empty());
+        assert!(image.arm64_runtime_function(0x2000).is_none());
+    }
+
+    #[test]
+    fn files_without_trailing_data_have_no_overlay() {
+        let bytes = TestImage::new("sample.dll").build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.overlay_offset(), None);
+        assert_eq!(image.overlay(), None);
+    }
+
+    #[test]
+    fn overlays_follow_the_last_raw_section() {
+        let bytes = TestImage::new("sample.dll").overlay(b"appended").build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.overlay_offset(), Some(0x3000));
+        assert_eq!(image.overlay(), Some(&b"appended"[..]));
+
+        // Raw data, not the section's RVA, decides where it starts.
+        let bytes = TestImage::new("sample.dll")
+            .raw_data(0x400, 0x1000)
+            .overlay(b"appended")
+            .build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.overlay_offset(), Some(0x1400));
+        assert_eq!(image.overlay(), Some(&b"appended"[..]));
+    }
+
+    #[test]
+    fn overlays_exclude_the_certificate_table() {
+        let mut trailing = Vec::from(&b"appended"[..]);
+        trailing.resize(8 + 0x100, 0xcc);
+        let signed = |overlay: &[u8], certificate_table: u32| {
+            TestImage::new("sample.dll")
+                .overlay(overlay)
+                .data_directory(ImageDirectoryEntry::Security, certificate_table, 0x100)
+                .build()
+        };
+
+        let bytes = signed(&trailing, 0x3008);
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.overlay(), Some(&b"appended"[..]));
+
+        // Only the certificate table is appended.
+        let bytes = signed(&trailing[8..], 0x3000);
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.overlay_offset(), None);
+    }
+
+    #[test]
+    fn mapped_images_have_no_overlay() {
+        let test_image = TestImage::new("sample.dll").overlay(b"appended");
+        let bytes = test_image.build();
+        let image = Image::from_mapped(&bytes).unwrap();
+        assert_eq!(image.overlay_offset(), None);
+        assert_eq!(image.overlay(), None);
+
+        let bytes = test_image.build_mapped();
+        let image = Image::from_mapped(&bytes).unwrap();
+        assert_eq!(image.overlay(), None);
+    }
+
+    #[test]
+    fn dos_stubs_end_at_the_nt_headers() {
+        // `e_lfanew` right after the DOS header leaves no room for a stub.
+        let bytes = TestImage::new("sample.dll").build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.dos_header().lfanew(), 0x40);
+        assert_eq!(image.dos_stub(), &[] as &[u8]);
+
+        let stub = b"\x0e\x1f\xba\x0e\x00\xb4\x09\xcd\x21\xb8\x01\x4c\xcd\x21This program cannot be run in DOS mode.\r\r\n$";
+        let bytes = TestImage::new("sample.dll").dos_stub(stub).build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.dos_header().lfanew(), 0x80);
+        // Padded up to the 8-byte aligned NT headers.
+        assert_eq!(image.dos_stub().len(), 0x40);
+        assert!(image.dos_stub().starts_with(stub));
+        assert!(image.dos_stub()[stub.len()..]
+            .iter()
+            .all(|byte| byte.eq(&0)));
+        assert!(image.rich_header().is_none());
+    }
+
+    /// `lea rcx, [rip + 0x100]`, nops, `mov rax, [rip - 0x17]`,
+    /// `call +0x30`, `jmp -0x21`, `je +0x10`, `jne -0x7f` and `jmp $`.
+    const CODE: [u8; 43] = [
+        0x48, 0x8d, 0x0d, 0x00, 0x01, 0x00, 0x00, // 0x2000
+        0x90, 0x90, 0x90, 0x90, 0x90, 0x90, 0x90, 0x90, 0x90, // 0x2007
+        0x48, 0x8b, 0x05, 0xe9, 0xff, 0xff, 0xff, // 0x2010
+        0xe8, 0x30, 0x00, 0x00, 0x00, // 0x2017
+        0xe9, 0xdf, 0xff, 0xff, 0xff, // 0x201c
+        0x0f, 0x84, 0x10, 0x00, 0x00, 0x00, // 0x2021
+        0x75, 0x81, // 0x2027
+        0xeb, 0xfe, // 0x2029
+    ];
+
+    /// [`CODE`] at 0x2000 and a `call` cut off by the end of the image.
+    fn code_image() -> TestImage {
+        TestImage::new("sample.dll")
+            .contents(0x2000, &CODE)
+            .contents(0x2ffe, &[0xe8, 0x00])
+            .raw_data(0x400, 0x2000)
+    }
+
+    #[test]
+    fn resolves_rip_relative_operands() {
+        let test_image = code_image();
+        let (file, mapped) = (test_image.build(), test_image.build_mapped());
+        for image in [Image::from_file(&file), Image::from_mapped(&mapped)] {
+            let image = image.unwrap();
+            assert_eq!(image.resolve_rip_relative(0x2000, 3, 7), Some(0x2107));
+            assert_eq!(image.resolve_rip_relative(0x2010, 3, 7), Some(0x2000));
+            // The displacement has to lie within the image.
+            assert_eq!(image.resolve_rip_relative(0x2ffe, 3, 7), None);
+            assert_eq!(image.resolve_rip_relative(0x2000, u32::MAX, 7), None);
+        }
+    }
+
+    #[test]
+    fn resolves_relative_branches() {
+        let test_image = code_image();
+        let (file, mapped) = (test_image.build(), test_image.build_mapped());
+        for image in [Image::from_file(&file), Image::from_mapped(&mapped)] {
+            let image = image.unwrap();
+            assert_eq!(image.resolve_relative_branch(0x2017), Some(0x204c));
+            assert_eq!(image.resolve_relative_branch(0x201c), Some(0x2000));
+            assert_eq!(image.resolve_relative_branch(0x2021), Some(0x2037));
+            assert_eq!(image.resolve_relative_branch(0x2027), Some(0x1faa));
+            assert_eq!(image.resolve_relative_branch(0x2029), Some(0x2029));
+            // Not a branch, and a `call` cut off by the end of the image.
+            assert_eq!(image.resolve_relative_branch(0x2007), None);
+            assert_eq!(image.resolve_relative_branch(0x2ffe), None);
+            assert_eq!(image.resolve_relative_branch(0x2fff), None);
+        }
+    }
+
+    #[test]
+    fn branches_may_not_leave_the_image() {
+        // `call -0x3000` and `jmp +0x1000` from 0x2000.
+        let bytes = TestImage::new("sample.dll")
+            .contents(0x2000, &[0xe8, 0x00, 0xd0, 0xff, 0xff])
+            .contents(0x2005, &[0xe9, 0x00, 0x10, 0x00, 0x00])
+            .build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.resolve_relative_branch(0x2000), None);
+        assert_eq!(image.resolve_relative_branch(0x2005), None);
+    }
+
+    #[test]
+    fn converts_between_rvas_and_addresses() {
+        let bytes = TestImage::new("sample.dll").build();
+        let file = Image::from_file(&bytes).unwrap();
+        assert_eq!(file.rva_to_address(0x2000), Some(0x1_8000_2000));
+        assert_eq!(file.address_to_rva(0x1_8000_2000), Some(0x2000));
+        assert_eq!(file.rva_to_address(0x3000), None);
+        assert_eq!(file.address_to_rva(0x1_8000_3000), None);
+        assert_eq!(file.address_to_rva(0x1_7fff_ffff), None);
+
+        let mapped = Image::from_mapped(&bytes).unwrap();
+        let base = bytes.as_ptr() as u64;
+        assert_eq!(mapped.rva_to_address(0x2000), Some(base + 0x2000));
+        assert_eq!(mapped.address_to_rva(base + 0x2000), Some(0x2000));
+
+        // A hostile preferred base must not overflow.
+        let bytes = TestImage::new("sample.dll")
+            .image_base(u64::MAX - 0xfff)
+            .build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.rva_to_address(0xfff), Some(u64::MAX));
+        assert_eq!(image.rva_to_address(0x1000), None);
+    }
+}
@@ -0,0 +1,257 @@
+/// An `IMAGE_ARM64_RUNTIME_FUNCTION_ENTRY` of the `.pdata` section. The low
+/// two bits of `unwind_data` select between an RVA of `.xdata` unwind
+/// information and unwind data packed into the entry itself.
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageArm64RuntimeFunction {
+    begin_address: u32,
+    unwind_data: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arm64UnwindFlag {
+    Xdata = 0,
+    PackedFunction = 1,
+    PackedFragment = 2,
+    Reserved = 3,
+}
+
+/// How the packed prolog saves the frame pointer and link register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arm64FrameChain {
+    /// `lr` is not saved.
+    Unchained = 0,
+    /// `lr` is saved next to the integer registers.
+    UnchainedSavedLr = 1,
+    /// Like `Chained`, with the return address signed by `pacibsp`.
+    ChainedPac = 2,
+    /// `fp` and `lr` are saved and `fp` points to the frame record.
+    Chained = 3,
+}
+
+/// The fields of packed unwind data, scaled to bytes where they count
+/// instructions or stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arm64PackedUnwind {
+    flag: Arm64UnwindFlag,
+    function_length: u32,
+    reg_f: u8,
+    reg_i: u8,
+    homes_parameters: bool,
+    frame_chain: Arm64FrameChain,
+    frame_size: u32,
+}
+
+impl ImageArm64RuntimeFunction {
+    pub fn begin_address(self: &Self) -> u32 {
+        self.begin_address
+    }
+
+    pub fn unwind_data(self: &Self) -> u32 {
+        self.unwind_data
+    }
+
+    pub fn flag(self: &Self) -> Arm64UnwindFlag {
+        match self.unwind_data & 0b11 {
+            0 => Arm64UnwindFlag::Xdata,
+            1 => Arm64UnwindFlag::PackedFunction,
+            2 => Arm64UnwindFlag::PackedFragment,
+            _ => Arm64UnwindFlag::Reserved,
+        }
+    }
+
+    pub fn is_packed(self: &Self) -> bool {
+        matches!(
+            self.flag(),
+            Arm64UnwindFlag::PackedFunction | Arm64UnwindFlag::PackedFragment
+        )
+    }
+
+    /// RVA of the `.xdata` record, if the unwind data is not packed.
+    pub fn xdata_rva(self: &Self) -> Option<u32> {
+        self.flag()
+            .eq(&Arm64UnwindFlag::Xdata)
+            .then_some(self.unwind_data)
+    }
+
+    pub fn packed(self: &Self) -> Option<Arm64PackedUnwind> {
+        if !self.is_packed() {
+            return None;
+        }
+        let bits = |shift: u32, width: u32| (self.unwind_data >> shift) & ((1 << width) - 1);
+        Some(Arm64PackedUnwind {
+            flag: self.flag(),
+            function_length: bits(2, 11) * 4,
+            reg_f: bits(13, 3) as u8,
+            reg_i: bits(16, 4) as u8,
+            homes_parameters: bits(20, 1).ne(&0),
+            frame_chain: match bits(21, 2) {
+                0 => Arm64FrameChain::Unchained,
+                1 => Arm64FrameChain::UnchainedSavedLr,
+                2 => Arm64FrameChain::ChainedPac,
+                _ => Arm64FrameChain::Chained,
+            },
+            frame_size: bits(23, 9) * 16,
+        })
+    }
+
+    /// The length in bytes of the function for an `.xdata` record whose
+    /// first word is `header`.
+    pub fn xdata_function_length(header: u32) -> u32 {
+        (header & 0x3ffff) * 4
+    }
+}
+
+impl Arm64PackedUnwind {
+    pub fn flag(self: &Self) -> &Arm64UnwindFlag {
+        &self.flag
+    }
+
+    pub fn function_length(self: &Self) -> &u32 {
+        &self.function_length
+    }
+
+    /// Number of `d8`-`d15` registers saved.
+    pub fn saved_fp_registers(self: &Self) -> u8 {
+        match self.reg_f {
+            0 => 0,
+            reg_f => reg_f + 1,
+        }
+    }
+
+    /// Number of `x19`-`x28` registers saved.
+    pub fn saved_integer_registers(self: &Self) -> &u8 {
+        &self.reg_i
+    }
+
+    /// Whether `x0`-`x7` are spilled to the stack on entry.
+    pub fn homes_parameters(self: &Self) -> &bool {
+        &self.homes_parameters
+    }
+
+    pub fn frame_chain(self: &Self) -> &Arm64FrameChain {
+        &self.frame_chain
+    }
+
+    /// Total stack allocated by the prolog.
+    pub fn frame_size(self: &Self) -> &u32 {
+        &self.frame_size
+    }
+
+    /// Bytes of the register save area, 16-byte aligned.
+    pub fn save_size(self: &Self) -> u32 {
+        let mut integer_size = 8 * self.reg_i as u32;
+        if self.frame_chain.eq(&Arm64FrameChain::UnchainedSavedLr) {
+            integer_size += 8;
+        }
+        let fp_size = 8 * self.saved_fp_registers() as u32;
+        let home_size = match self.homes_parameters {
+            true => 64,
+            false => 0,
+        };
+        (integer_size + fp_size + home_size + 0xf) & !0xf
+    }
+
+    /// Bytes of locals, including the frame record of chained functions.
+    pub fn local_size(self: &Self) -> u32 {
+        self.frame_size.saturating_sub(self.save_size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arm64FrameChain, Arm64PackedUnwind, Arm64UnwindFlag, ImageArm64RuntimeFunction};
+
+    fn runtime_function(unwind_data: u32) -> ImageArm64RuntimeFunction {
+        ImageArm64RuntimeFunction {
+            begin_address: 0x1000,
+            unwind_data,
+        }
+    }
+
+    fn packed(
+        function_length: u32,
+        reg_i: u8,
+        frame_chain: Arm64FrameChain,
+        frame_size: u32,
+    ) -> Arm64PackedUnwind {
+        Arm64PackedUnwind {
+            flag: Arm64UnwindFlag::PackedFunction,
+            function_length,
+            reg_f: 0,
+            reg_i,
+            homes_parameters: false,
+            frame_chain,
+            frame_size,
+        }
+    }
+
+    #[test]
+    fn decodes_packed_words_of_a_real_binary() {
+        // `.pdata` words of an MSVC ARM64 executable, decoded by llvm-readobj.
+        let words = [
+            (0x01e3005d, packed(92, 3, Arm64FrameChain::Chained, 48)),
+            (0x02660089, packed(136, 6, Arm64FrameChain::Chained, 64)),
+            (0x00e0005d, packed(92, 0, Arm64FrameChain::Chained, 16)),
+            (0x00800019, packed(24, 0, Arm64FrameChain::Unchained, 16)),
+            (0x046902a1, packed(672, 9, Arm64FrameChain::Chained, 128)),
+            (0x06e90489, packed(1160, 9, Arm64FrameChain::Chained, 208)),
+        ];
+        for (word, expected) in words {
+            let runtime_function = runtime_function(word);
+            assert_eq!(runtime_function.flag(), Arm64UnwindFlag::PackedFunction);
+            assert!(runtime_function.xdata_rva().is_none());
+            assert_eq!(runtime_function.packed(), Some(expected), "{:#x}", word);
+        }
+
+        // x19-x21 and the frame record fill 48 bytes, so no locals.
+        let unwind = runtime_function(0x01e3005d).packed().unwrap();
+        assert_eq!(unwind.save_size(), 32);
+        assert_eq!(unwind.local_size(), 16);
+    }
+
+    #[test]
+    fn decodes_every_field_at_its_limit() {
+        // A fragment with every field set to its maximum and `CR` = 1.
+        let word = 2 | 0x7ff << 2 | 7 << 13 | 0xf << 16 | 1 << 20 | 1 << 21 | 0x1ff << 23;
+        let unwind = runtime_function(word).packed().unwrap();
+        assert_eq!(unwind.flag(), &Arm64UnwindFlag::PackedFragment);
+        assert_eq!(unwind.function_length(), &(0x7ff * 4));
+        assert_eq!(unwind.saved_fp_registers(), 8);
+        assert_eq!(unwind.saved_integer_registers(), &15);
+        assert_eq!(unwind.homes_parameters(), &true);
+        assert_eq!(unwind.frame_chain(), &Arm64FrameChain::UnchainedSavedLr);
+        assert_eq!(unwind.frame_size(), &(0x1ff * 16));
+        // 15 integer registers and lr, 8 FP registers and 8 parameters.
+        assert_eq!(unwind.save_size(), 128 + 64 + 64);
+        assert_eq!(unwind.local_size(), 0x1ff * 16 - 256);
+
+        let pac = runtime_function(1 | 2 << 21).packed().unwrap();
+        assert_eq!(pac.frame_chain(), &Arm64FrameChain::ChainedPac);
+        assert_eq!(pac.local_size(), 0);
+    }
+
+    #[test]
+    fn leaves_other_words_unpacked() {
+        let xdata = runtime_function(0x21d2c);
+        assert_eq!(xdata.flag(), Arm64UnwindFlag::Xdata);
+        assert!(!xdata.is_packed());
+        assert!(xdata.packed().is_none());
+        assert_eq!(xdata.xdata_rva(), Some(0x21d2c));
+
+        let reserved = runtime_function(0x01e3005f);
+        assert_eq!(reserved.flag(), Arm64UnwindFlag::Reserved);
+        assert!(reserved.packed().is_none());
+        assert!(reserved.xdata_rva().is_none());
+
+        // The low 18 bits of an `.xdata` header count instructions.
+        assert_eq!(
+            ImageArm64RuntimeFunction::xdata_function_length(0x0880_0007),
+            28
+        );
+        assert_eq!(
+            ImageArm64RuntimeFunction::xdata_function_length(0xfffc_0000),
+            0
+        );
+    }
+}
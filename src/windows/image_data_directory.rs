@@ -1,17 +1,32 @@
-#[repr(C)]
-#[derive(Debug)]
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
 pub struct ImageDataDirectory {
     virtual_address: u32,
     size: u32,
 }
 
 impl ImageDataDirectory {
-    pub fn virtual_address(self: &Self) -> &u32 {
-        &self.virtual_address
+    /// Stands in for directories past `NumberOfRvaAndSizes`.
+    pub const EMPTY: ImageDataDirectory = ImageDataDirectory {
+        virtual_address: 0,
+        size: 0,
+    };
+
+    pub fn virtual_address(self: &Self) -> u32 {
+        self.virtual_address
+    }
+
+    pub fn size(self: &Self) -> u32 {
+        self.size
+    }
+
+    pub fn is_present(self: &Self) -> bool {
+        self.virtual_address().ne(&0) && self.size().ne(&0)
     }
 
     pub fn is_forwarded_export(self: &Self, export_function_rva: u32) -> bool {
-        export_function_rva.ge(self.virtual_address())
-            && export_function_rva.le(&(self.virtual_address + self.size))
+        export_function_rva
+            .checked_sub(self.virtual_address())
+            .is_some_and(|offset| offset.lt(&self.size()))
     }
 }
@@ -0,0 +1,64 @@
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageDebugDirectory {
+    characteristics: u32,
+    time_date_stamp: u32,
+    major_version: u16,
+    minor_version: u16,
+    debug_type: u32,
+    size_of_data: u32,
+    address_of_raw_data: u32,
+    pointer_to_raw_data: u32,
+}
+
+#[derive(Debug)]
+pub enum ImageDebugType {
+    Unknown = 0,
+    Coff = 1,
+    CodeView = 2,
+    Fpo = 3,
+    Misc = 4,
+    Exception = 5,
+    Fixup = 6,
+    OmapToSrc = 7,
+    OmapFromSrc = 8,
+    Borland = 9,
+    Reserved10 = 10,
+    Clsid = 11,
+    VcFeature = 12,
+    Pogo = 13,
+    Iltcg = 14,
+    Mpx = 15,
+    Repro = 16,
+    ExDllCharacteristics = 20,
+}
+
+#[derive(Debug)]
+pub enum ImageCetCharacteristics {
+    Compat = 0x01,
+    CompatStrictMode = 0x02,
+    SetContextIpValidationRelaxedMode = 0x04,
+    DynamicApisAllowInProc = 0x08,
+}
+
+impl ImageDebugDirectory {
+    pub fn debug_type(self: &Self) -> u32 {
+        self.debug_type
+    }
+
+    pub fn is_type(self: &Self, debug_type: ImageDebugType) -> bool {
+        self.debug_type().eq(&(debug_type as u32))
+    }
+
+    pub fn size_of_data(self: &Self) -> u32 {
+        self.size_of_data
+    }
+
+    pub fn address_of_raw_data(self: &Self) -> u32 {
+        self.address_of_raw_data
+    }
+
+    pub fn pointer_to_raw_data(self: &Self) -> u32 {
+        self.pointer_to_raw_data
+    }
+}
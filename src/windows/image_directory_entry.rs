@@ -1,4 +1,3 @@
-#[allow(dead_code)]
 #[derive(Debug)]
 pub enum ImageDirectoryEntry {
     Architecture = 7,
@@ -1,7 +1,9 @@
-use super::ImageNtHeaders;
+use core::{mem::size_of, slice::from_raw_parts};
 
-#[repr(C)]
-#[derive(Debug)]
+use super::{ImageNtHeaders, RichHeader};
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
 pub struct ImageDosHeader {
     e_magic: u16,
     e_cblp: u16,
@@ -25,8 +27,41 @@ pub struct ImageDosHeader {
 }
 
 impl ImageDosHeader {
+    pub const MAGIC: u16 = 0x5a4d;
+
+    pub fn magic(self: &Self) -> u16 {
+        self.e_magic
+    }
+
+    pub fn lfanew(self: &Self) -> i32 {
+        self.e_lfanew
+    }
+
     pub fn nt_headers(self: &Self) -> &ImageNtHeaders {
         let this = self as *const ImageDosHeader as *const ImageNtHeaders;
         unsafe { &*this.byte_offset(self.e_lfanew as isize) }
     }
+
+    /// The bytes from the start of the image up to the NT headers.
+    pub fn header_bytes(self: &Self) -> &[u8] {
+        let this = self as *const ImageDosHeader as *const u8;
+        unsafe { from_raw_parts(this, self.e_lfanew.max(0) as usize) }
+    }
+
+    pub fn rich_header(self: &Self) -> Option<RichHeader<'_>> {
+        RichHeader::parse(self.header_bytes())
+    }
+
+    /// The real-mode stub program that follows the DOS header, up to the
+    /// Rich header if there is one, otherwise up to the NT headers.
+    pub fn dos_stub(self: &Self) -> &[u8] {
+        let header_bytes = self.header_bytes();
+        let end = match self.rich_header() {
+            Some(rich_header) => rich_header.offset().clone(),
+            None => header_bytes.len(),
+        };
+        header_bytes
+            .get(size_of::<ImageDosHeader>()..end)
+            .unwrap_or(&[])
+    }
 }
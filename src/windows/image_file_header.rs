@@ -1,5 +1,7 @@
-#[repr(C)]
-#[derive(Debug)]
+use core::ops::BitAnd;
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
 pub struct ImageFileHeader {
     machine: ImageFileMachine,
     number_of_sections: u16,
@@ -11,19 +13,75 @@ pub struct ImageFileHeader {
 }
 
 #[repr(transparent)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct ImageFileMachine(pub u16);
 
 #[repr(transparent)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct ImageFileCharacteristics(pub u16);
 
+#[derive(Debug)]
+pub enum ImageFile {
+    RelocsStripped = 0x0001,
+    ExecutableImage = 0x0002,
+    LargeAddressAware = 0x0020,
+    Machine32Bit = 0x0100,
+    DebugStripped = 0x0200,
+    RemovableRunFromSwap = 0x0400,
+    NetRunFromSwap = 0x0800,
+    System = 0x1000,
+    Dll = 0x2000,
+    UpSystemOnly = 0x4000,
+}
+
+/// `IMAGE_FILE_MACHINE_*`. ARM64EC images carry the `Amd64` machine and
+/// ARM64X images the `Arm64` machine in their file header; `Arm64Ec` and
+/// `Arm64X` only appear in their CHPE metadata and hybrid headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageMachine {
+    Unknown = 0x0000,
+    I386 = 0x014c,
+    Arm = 0x01c0,
+    ArmNt = 0x01c4,
+    Amd64 = 0x8664,
+    Arm64 = 0xaa64,
+    Arm64Ec = 0xa641,
+    Arm64X = 0xa64e,
+}
+
 impl ImageFileHeader {
-    pub fn number_of_sections(self: &Self) -> &u16 {
-        &self.number_of_sections
+    pub fn machine(self: &Self) -> u16 {
+        self.machine.0
+    }
+
+    pub fn is_machine(self: &Self, machine: ImageMachine) -> bool {
+        self.machine().eq(&(machine as u16))
+    }
+
+    pub fn number_of_sections(self: &Self) -> u16 {
+        self.number_of_sections
+    }
+
+    pub fn time_date_stamp(self: &Self) -> u32 {
+        self.time_date_stamp
+    }
+
+    pub fn pointer_to_symbol_table(self: &Self) -> u32 {
+        self.pointer_to_symbol_table
+    }
+
+    pub fn number_of_symbols(self: &Self) -> u32 {
+        self.number_of_symbols
+    }
+
+    pub fn size_of_optional_header(self: &Self) -> u16 {
+        self.size_of_optional_header
     }
 
-    pub fn size_of_optional_header(self: &Self) -> &u16 {
-        &self.size_of_optional_header
+    pub fn has_characteristic(self: &Self, characteristic: ImageFile) -> bool {
+        self.characteristics
+            .0
+            .bitand(characteristic as u16)
+            .ne(&(0 as u16))
     }
 }
@@ -0,0 +1,244 @@
+use core::{
+    mem::{offset_of, size_of_val},
+    ops::BitAnd,
+};
+
+/// `IMAGE_LOAD_CONFIG_DIRECTORY32` of PE32 images.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageLoadConfigDirectory32 {
+    size: u32,
+    time_date_stamp: u32,
+    major_version: u16,
+    minor_version: u16,
+    global_flags_clear: u32,
+    global_flags_set: u32,
+    critical_section_default_timeout: u32,
+    de_commit_free_block_threshold: u32,
+    de_commit_total_free_threshold: u32,
+    lock_prefix_table: u32,
+    maximum_allocation_size: u32,
+    virtual_memory_threshold: u32,
+    process_heap_flags: u32,
+    process_affinity_mask: u32,
+    csd_version: u16,
+    dependent_load_flags: u16,
+    edit_list: u32,
+    security_cookie: u32,
+    se_handler_table: u32,
+    se_handler_count: u32,
+    guard_cf_check_function_pointer: u32,
+    guard_cf_dispatch_function_pointer: u32,
+    guard_cf_function_table: u32,
+    guard_cf_function_count: u32,
+    guard_flags: u32,
+    code_integrity: ImageLoadConfigCodeIntegrity,
+}
+
+/// `IMAGE_LOAD_CONFIG_DIRECTORY64` of PE32+ images.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageLoadConfigDirectory64 {
+    size: u32,
+    time_date_stamp: u32,
+    major_version: u16,
+    minor_version: u16,
+    global_flags_clear: u32,
+    global_flags_set: u32,
+    critical_section_default_timeout: u32,
+    de_commit_free_block_threshold: u64,
+    de_commit_total_free_threshold: u64,
+    lock_prefix_table: u64,
+    maximum_allocation_size: u64,
+    virtual_memory_threshold: u64,
+    process_affinity_mask: u64,
+    process_heap_flags: u32,
+    csd_version: u16,
+    dependent_load_flags: u16,
+    edit_list: u64,
+    security_cookie: u64,
+    se_handler_table: u64,
+    se_handler_count: u64,
+    guard_cf_check_function_pointer: u64,
+    guard_cf_dispatch_function_pointer: u64,
+    guard_cf_function_table: u64,
+    guard_cf_function_count: u64,
+    guard_flags: u32,
+    code_integrity: ImageLoadConfigCodeIntegrity,
+}
+
+/// A load config directory copied out of an image, in the format of the
+/// image's optional header. Fields beyond the directory's `size` read as
+/// `None`.
+#[derive(Debug, Clone, Copy)]
+pub enum ImageLoadConfigDirectory {
+    Pe32(ImageLoadConfigDirectory32),
+    Pe64(ImageLoadConfigDirectory64),
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageLoadConfigCodeIntegrity {
+    flags: u16,
+    catalog: u16,
+    catalog_offset: u32,
+    reserved: u32,
+}
+
+#[derive(Debug)]
+pub enum ImageGuard {
+    CfInstrumented = 0x00000100,
+    CfwInstrumented = 0x00000200,
+    CfFunctionTablePresent = 0x00000400,
+    SecurityCookieUnused = 0x00000800,
+    ProtectDelayLoadIat = 0x00001000,
+    DelayLoadIatInItsOwnSection = 0x00002000,
+    CfExportSuppressionInfoPresent = 0x00004000,
+    CfEnableExportSuppression = 0x00008000,
+    CfLongJumpTablePresent = 0x00010000,
+    RfInstrumented = 0x00020000,
+    RfEnable = 0x00040000,
+    RfStrict = 0x00080000,
+    RetpolinePresent = 0x00100000,
+    EhContinuationTablePresent = 0x00400000,
+    XfgEnabled = 0x00800000,
+}
+
+macro_rules! load_config_directory_fields {
+    ($directory:ty) => {
+        impl $directory {
+            pub fn size(self: &Self) -> &u32 {
+                &self.size
+            }
+
+            pub fn security_cookie(self: &Self) -> Option<u64> {
+                self.has_field(
+                    offset_of!(Self, security_cookie),
+                    size_of_val(&self.security_cookie),
+                )
+                .then_some(self.security_cookie as u64)
+            }
+
+            pub fn se_handler_table(self: &Self) -> Option<u64> {
+                self.has_field(
+                    offset_of!(Self, se_handler_table),
+                    size_of_val(&self.se_handler_table),
+                )
+                .then_some(self.se_handler_table as u64)
+            }
+
+            pub fn se_handler_count(self: &Self) -> Option<u64> {
+                self.has_field(
+                    offset_of!(Self, se_handler_count),
+                    size_of_val(&self.se_handler_count),
+                )
+                .then_some(self.se_handler_count as u64)
+            }
+
+            pub fn guard_cf_check_function_pointer(self: &Self) -> Option<u64> {
+                self.has_field(
+                    offset_of!(Self, guard_cf_check_function_pointer),
+                    size_of_val(&self.guard_cf_check_function_pointer),
+                )
+                .then_some(self.guard_cf_check_function_pointer as u64)
+            }
+
+            pub fn guard_flags(self: &Self) -> Option<u32> {
+                self.has_field(
+                    offset_of!(Self, guard_flags),
+                    size_of_val(&self.guard_flags),
+                )
+                .then_some(self.guard_flags)
+            }
+
+            pub fn code_integrity(self: &Self) -> Option<&ImageLoadConfigCodeIntegrity> {
+                self.has_field(
+                    offset_of!(Self, code_integrity),
+                    size_of_val(&self.code_integrity),
+                )
+                .then_some(&self.code_integrity)
+            }
+
+            fn has_field(self: &Self, offset: usize, size: usize) -> bool {
+                (self.size as usize).ge(&(offset + size))
+            }
+        }
+    };
+}
+
+load_config_directory_fields!(ImageLoadConfigDirectory32);
+load_config_directory_fields!(ImageLoadConfigDirectory64);
+
+impl ImageLoadConfigDirectory {
+    pub fn size(self: &Self) -> &u32 {
+        match self {
+            ImageLoadConfigDirectory::Pe32(directory) => directory.size(),
+            ImageLoadConfigDirectory::Pe64(directory) => directory.size(),
+        }
+    }
+
+    pub fn security_cookie(self: &Self) -> Option<u64> {
+        match self {
+            ImageLoadConfigDirectory::Pe32(directory) => directory.security_cookie(),
+            ImageLoadConfigDirectory::Pe64(directory) => directory.security_cookie(),
+        }
+    }
+
+    pub fn se_handler_table(self: &Self) -> Option<u64> {
+        match self {
+            ImageLoadConfigDirectory::Pe32(directory) => directory.se_handler_table(),
+            ImageLoadConfigDirectory::Pe64(directory) => directory.se_handler_table(),
+        }
+    }
+
+    pub fn se_handler_count(self: &Self) -> Option<u64> {
+        match self {
+            ImageLoadConfigDirectory::Pe32(directory) => directory.se_handler_count(),
+            ImageLoadConfigDirectory::Pe64(directory) => directory.se_handler_count(),
+        }
+    }
+
+    pub fn guard_cf_check_function_pointer(self: &Self) -> Option<u64> {
+        match self {
+            ImageLoadConfigDirectory::Pe32(directory) => {
+                directory.guard_cf_check_function_pointer()
+            }
+            ImageLoadConfigDirectory::Pe64(directory) => {
+                directory.guard_cf_check_function_pointer()
+            }
+        }
+    }
+
+    pub fn guard_flags(self: &Self) -> Option<u32> {
+        match self {
+            ImageLoadConfigDirectory::Pe32(directory) => directory.guard_flags(),
+            ImageLoadConfigDirectory::Pe64(directory) => directory.guard_flags(),
+        }
+    }
+
+    pub fn has_guard_flag(self: &Self, flag: ImageGuard) -> bool {
+        self.guard_flags()
+            .is_some_and(|guard_flags| guard_flags.bitand(flag as u32).ne(&0))
+    }
+
+    pub fn code_integrity(self: &Self) -> Option<&ImageLoadConfigCodeIntegrity> {
+        match self {
+            ImageLoadConfigDirectory::Pe32(directory) => directory.code_integrity(),
+            ImageLoadConfigDirectory::Pe64(directory) => directory.code_integrity(),
+        }
+    }
+}
+
+impl ImageLoadConfigCodeIntegrity {
+    pub fn flags(self: &Self) -> &u16 {
+        &self.flags
+    }
+
+    pub fn catalog(self: &Self) -> &u16 {
+        &self.catalog
+    }
+
+    pub fn catalog_offset(self: &Self) -> &u32 {
+        &self.catalog_offset
+    }
+}
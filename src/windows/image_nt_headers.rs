@@ -1,22 +1,41 @@
-use core::slice::from_raw_parts;
+use core::{mem::size_of, slice::from_raw_parts};
 
 use super::{ImageFileHeader, ImageOptionalHeader, ImageSectionHeaders};
 
-#[repr(C)]
-#[derive(Debug)]
+/// The signature and file header of `IMAGE_NT_HEADERS`. The optional header
+/// that follows is PE32 or PE32+ depending on its magic, see
+/// [`ImageNtHeaders::optional_header`].
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
 pub struct ImageNtHeaders {
     signature: u32,
     file_header: ImageFileHeader,
-    optional_header: ImageOptionalHeader,
 }
 
 impl ImageNtHeaders {
-    pub fn optional_header(self: &Self) -> &ImageOptionalHeader {
-        &self.optional_header
+    pub const SIGNATURE: u32 = 0x00004550;
+
+    pub fn signature(self: &Self) -> u32 {
+        self.signature
+    }
+
+    pub fn file_header(self: &Self) -> &ImageFileHeader {
+        &self.file_header
+    }
+
+    /// Offset of the optional header from the start of the NT headers.
+    pub const OPTIONAL_HEADER_OFFSET: usize = size_of::<ImageNtHeaders>();
+
+    pub fn optional_header(self: &Self) -> ImageOptionalHeader<'_> {
+        let optional_header = (self as *const ImageNtHeaders)
+            .wrapping_byte_add(ImageNtHeaders::OPTIONAL_HEADER_OFFSET)
+            as *const u16;
+        unsafe { ImageOptionalHeader::from_ptr(optional_header) }
     }
 
     pub fn section_headers(self: &Self) -> &[ImageSectionHeaders] {
-        let optional_header = self.optional_header() as *const ImageOptionalHeader;
+        let optional_header = (self as *const ImageNtHeaders)
+            .wrapping_byte_add(ImageNtHeaders::OPTIONAL_HEADER_OFFSET);
         unsafe {
             let section_headers = optional_header
                 .byte_offset(self.file_header.size_of_optional_header().clone() as isize)
@@ -1,9 +1,11 @@
+use core::ops::BitAnd;
+
 use super::{ImageDataDirectory, ImageDirectoryEntry};
 
-#[cfg(target_arch = "x86")]
-#[repr(C)]
-#[derive(Debug)]
-pub struct ImageOptionalHeader {
+/// `IMAGE_OPTIONAL_HEADER32` of PE32 images.
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageOptionalHeader32 {
     magic: u16,
     major_linker_version: u8,
     minor_linker_version: u8,
@@ -37,10 +39,10 @@ pub struct ImageOptionalHeader {
     data_directory: [ImageDataDirectory; 16],
 }
 
-#[cfg(target_arch = "x86_64")]
-#[repr(C)]
-#[derive(Debug)]
-pub struct ImageOptionalHeader {
+/// `IMAGE_OPTIONAL_HEADER64` of PE32+ images.
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageOptionalHeader64 {
     magic: u16,
     major_linker_version: u8,
     minor_linker_version: u8,
@@ -73,8 +75,115 @@ pub struct ImageOptionalHeader {
     data_directory: [ImageDataDirectory; 16],
 }
 
-impl ImageOptionalHeader {
-    pub fn data_directory(self: &Self, directory: ImageDirectoryEntry) -> &ImageDataDirectory {
-        &self.data_directory[directory as usize]
+/// The optional header of either format, told apart by its magic rather
+/// than by the architecture the code is compiled for.
+#[derive(Debug, Clone, Copy)]
+pub enum ImageOptionalHeader<'a> {
+    Pe32(&'a ImageOptionalHeader32),
+    Pe64(&'a ImageOptionalHeader64),
+}
+
+#[derive(Debug)]
+pub enum ImageDllCharacteristics {
+    HighEntropyVa = 0x0020,
+    DynamicBase = 0x0040,
+    ForceIntegrity = 0x0080,
+    NxCompat = 0x0100,
+    NoIsolation = 0x0200,
+    NoSeh = 0x0400,
+    NoBind = 0x0800,
+    AppContainer = 0x1000,
+    WdmDriver = 0x2000,
+    GuardCf = 0x4000,
+    TerminalServerAware = 0x8000,
+}
+
+impl ImageOptionalHeader32 {
+    pub const MAGIC: u16 = 0x10b;
+}
+
+impl ImageOptionalHeader64 {
+    pub const MAGIC: u16 = 0x20b;
+}
+
+impl<'a> ImageOptionalHeader<'a> {
+    pub const MAGIC32: u16 = ImageOptionalHeader32::MAGIC;
+    pub const MAGIC64: u16 = ImageOptionalHeader64::MAGIC;
+
+    /// # Safety
+    ///
+    /// `optional_header` must point to an optional header whose magic is
+    /// [`ImageOptionalHeader::MAGIC32`] or [`ImageOptionalHeader::MAGIC64`],
+    /// valid for `'a`.
+    pub unsafe fn from_ptr(optional_header: *const u16) -> Self {
+        match unsafe { optional_header.read_unaligned() } {
+            ImageOptionalHeader32::MAGIC => {
+                ImageOptionalHeader::Pe32(unsafe { &*(optional_header as *const _) })
+            }
+            _ => ImageOptionalHeader::Pe64(unsafe { &*(optional_header as *const _) }),
+        }
+    }
+
+    pub fn magic(self: &Self) -> u16 {
+        match self {
+            ImageOptionalHeader::Pe32(header) => header.magic,
+            ImageOptionalHeader::Pe64(header) => header.magic,
+        }
+    }
+
+    pub fn is_pe32(self: &Self) -> bool {
+        matches!(self, ImageOptionalHeader::Pe32(_))
+    }
+
+    pub fn image_base(self: &Self) -> u64 {
+        match self {
+            ImageOptionalHeader::Pe32(header) => header.image_base as u64,
+            ImageOptionalHeader::Pe64(header) => header.image_base,
+        }
+    }
+
+    pub fn size_of_image(self: &Self) -> u32 {
+        match self {
+            ImageOptionalHeader::Pe32(header) => header.size_of_image,
+            ImageOptionalHeader::Pe64(header) => header.size_of_image,
+        }
+    }
+
+    pub fn size_of_headers(self: &Self) -> u32 {
+        match self {
+            ImageOptionalHeader::Pe32(header) => header.size_of_headers,
+            ImageOptionalHeader::Pe64(header) => header.size_of_headers,
+        }
+    }
+
+    pub fn has_dll_characteristic(self: &Self, characteristic: ImageDllCharacteristics) -> bool {
+        let dll_characteristics = match self {
+            ImageOptionalHeader::Pe32(header) => header.dll_characteristics,
+            ImageOptionalHeader::Pe64(header) => header.dll_characteristics,
+        };
+        dll_characteristics
+            .bitand(characteristic as u16)
+            .ne(&(0 as u16))
+    }
+
+    pub fn number_of_rva_and_sizes(self: &Self) -> u32 {
+        match self {
+            ImageOptionalHeader::Pe32(header) => header.number_of_rva_and_sizes,
+            ImageOptionalHeader::Pe64(header) => header.number_of_rva_and_sizes,
+        }
+    }
+
+    /// An empty directory for entries past
+    /// [`ImageOptionalHeader::number_of_rva_and_sizes`], whose slots hold
+    /// whatever follows the header, usually the section table.
+    pub fn data_directory(self: &Self, directory: ImageDirectoryEntry) -> &'a ImageDataDirectory {
+        let index = directory as usize;
+        if (index as u32).ge(&self.number_of_rva_and_sizes()) {
+            return &ImageDataDirectory::EMPTY;
+        }
+        match self {
+            ImageOptionalHeader::Pe32(header) => &header.data_directory[index],
+            ImageOptionalHeader::Pe64(header) => &header.data_directory[index],
+        }
     }
 }
@@ -1,7 +1,9 @@
-use core::{ops::BitAnd, slice::from_raw_parts};
+use core::ops::BitAnd;
 
-#[repr(C)]
-#[derive(Debug)]
+use super::{Image, ImageLayout};
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
 pub struct ImageSectionHeaders {
     name: [u8; 8],
     virtual_size: u32,
@@ -15,49 +17,284 @@ pub struct ImageSectionHeaders {
     characteristics: u32,
 }
 
-enum ImageSection {
+#[repr(u32)]
+#[derive(Debug)]
+pub enum ImageSection {
+    TypeNoPad = 0x00000008,
+    CntCode = 0x00000020,
+    CntInitializedData = 0x00000040,
+    CntUninitializedData = 0x00000080,
+    LnkOther = 0x00000100,
+    LnkInfo = 0x00000200,
+    LnkRemove = 0x00000800,
+    LnkComdat = 0x00001000,
+    NoDeferSpecExc = 0x00004000,
+    Gprel = 0x00008000,
+    MemPurgeable = 0x00020000,
+    MemLocked = 0x00040000,
+    MemPreload = 0x00080000,
+    LnkNrelocOvfl = 0x01000000,
+    MemDiscardable = 0x02000000,
+    MemNotCached = 0x04000000,
+    MemNotPaged = 0x08000000,
+    MemShared = 0x10000000,
     MemExecute = 0x20000000,
     MemRead = 0x40000000,
+    MemWrite = 0x80000000,
 }
 
 impl ImageSectionHeaders {
-    #[allow(dead_code)]
+    const ALIGN_MASK: u32 = 0x00f00000;
+    const ALIGN_SHIFT: u32 = 20;
+
     pub fn name(self: &Self) -> &[u8; 8] {
         &self.name
     }
 
-    pub fn virtual_address(self: &Self) -> &u32 {
-        &self.virtual_address
+    /// The name as stored in the header, without its NUL padding. Long
+    /// names appear here as `/123` string table references.
+    pub fn short_name(self: &Self) -> &[u8] {
+        let length = self
+            .name
+            .iter()
+            .position(|byte| byte.eq(&0))
+            .unwrap_or(self.name.len());
+        &self.name[..length]
     }
 
-    pub fn virtual_size(self: &Self) -> &u32 {
-        &self.virtual_size
+    pub fn has_name(self: &Self, name: &str) -> bool {
+        self.short_name().eq(name.as_bytes())
     }
 
-    pub fn is_executable(self: &Self) -> bool {
+    pub fn virtual_address(self: &Self) -> u32 {
+        self.virtual_address
+    }
+
+    pub fn virtual_size(self: &Self) -> u32 {
+        self.virtual_size
+    }
+
+    pub fn size_of_raw_data(self: &Self) -> u32 {
+        self.size_of_raw_data
+    }
+
+    pub fn pointer_to_raw_data(self: &Self) -> u32 {
+        self.pointer_to_raw_data
+    }
+
+    /// Size of the section once mapped, falling back to the raw size for
+    /// linkers that leave `virtual_size` zeroed.
+    pub fn mapped_size(self: &Self) -> u32 {
+        match self.virtual_size {
+            0 => self.size_of_raw_data,
+            virtual_size => virtual_size,
+        }
+    }
+
+    pub fn contains_rva(self: &Self, rva: u32) -> bool {
+        rva.checked_sub(self.virtual_address)
+            .is_some_and(|section_offset| section_offset.lt(&self.mapped_size()))
+    }
+
+    pub fn contains_offset(self: &Self, offset: u32) -> bool {
+        offset
+            .checked_sub(self.pointer_to_raw_data)
+            .is_some_and(|section_offset| section_offset.lt(&self.size_of_raw_data()))
+    }
+
+    /// Returns `None` when the RVA lies outside the section or in its
+    /// zero-filled tail, which has no backing bytes in the file.
+    pub fn rva_to_offset(self: &Self, rva: u32) -> Option<u32> {
+        let section_offset = rva.checked_sub(self.virtual_address)?;
+        (section_offset.lt(&self.mapped_size()) && section_offset.lt(&self.size_of_raw_data()))
+            .then(|| self.pointer_to_raw_data.checked_add(section_offset))
+            .flatten()
+    }
+
+    pub fn offset_to_rva(self: &Self, offset: u32) -> Option<u32> {
+        let section_offset = offset.checked_sub(self.pointer_to_raw_data)?;
+        (section_offset.lt(&self.mapped_size()) && section_offset.lt(&self.size_of_raw_data()))
+            .then(|| self.virtual_address.checked_add(section_offset))
+            .flatten()
+    }
+
+    pub fn characteristics(self: &Self) -> u32 {
         self.characteristics
-            .bitand(ImageSection::MemExecute as u32)
-            .ne(&(0 as u32))
     }
 
-    pub fn is_readable(self: &Self) -> bool {
+    pub fn has_characteristic(self: &Self, characteristic: ImageSection) -> bool {
         self.characteristics
-            .bitand(ImageSection::MemRead as u32)
+            .bitand(characteristic as u32)
             .ne(&(0 as u32))
     }
 
+    /// The `IMAGE_SCN_ALIGN_*` value in bytes. Only object files carry it,
+    /// so it is `None` for linked images.
+    pub fn alignment(self: &Self) -> Option<u32> {
+        match self.characteristics.bitand(ImageSectionHeaders::ALIGN_MASK)
+            >> ImageSectionHeaders::ALIGN_SHIFT
+        {
+            0 | 15 => None,
+            exponent => Some(1 << (exponent - 1)),
+        }
+    }
+
+    pub fn is_executable(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::MemExecute)
+    }
+
+    pub fn is_readable(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::MemRead)
+    }
+
+    pub fn is_writable(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::MemWrite)
+    }
+
+    pub fn is_discardable(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::MemDiscardable)
+    }
+
+    pub fn is_shared(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::MemShared)
+    }
+
+    pub fn contains_code(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::CntCode)
+    }
+
+    pub fn contains_initialized_data(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::CntInitializedData)
+    }
+
+    pub fn contains_uninitialized_data(self: &Self) -> bool {
+        self.has_characteristic(ImageSection::CntUninitializedData)
+    }
+
+    /// The section contents as laid out in `image`. Mapped images cover the
+    /// whole virtual size; file images only hold the raw data, and the loader
+    /// zero-fills [`ImageSectionHeaders::zero_fill_size`] bytes after it.
+    pub fn data<'a>(self: &Self, image: &Image<'a>) -> Option<&'a [u8]> {
+        match image.layout() {
+            ImageLayout::Mapped => {
+                image.slice_at_rva(self.virtual_address, self.mapped_size() as usize)
+            }
+            ImageLayout::File => {
+                let offset = self.pointer_to_raw_data as usize;
+                let size = self.size_of_raw_data.min(self.mapped_size()) as usize;
+                image.bytes().get(offset..offset.checked_add(size)?)
+            }
+        }
+    }
+
+    pub fn zero_fill_size(self: &Self) -> u32 {
+        self.mapped_size().saturating_sub(self.size_of_raw_data)
+    }
+
     pub fn is_code_section(self: &Self) -> bool {
-        let slice = unsafe { from_raw_parts(&self.name as *const u8, 5) };
-        self.is_executable() && slice.eq(b".text")
+        self.is_executable() && self.has_name(".text")
     }
 
     pub fn is_vmt_section(self: &Self) -> bool {
-        let slice = unsafe { from_raw_parts(&self.name as *const u8, 6) };
-        self.is_readable() && slice.eq(b".rdata")
+        self.is_readable() && self.has_name(".rdata")
     }
 
     pub fn is_data_section(self: &Self) -> bool {
-        let slice = unsafe { from_raw_parts(&self.name as *const u8, 5) };
-        self.is_readable() && slice.eq(b".data")
+        self.is_readable() && self.has_name(".data")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImageSection;
+    use crate::windows::{test_image::TestImage, Image};
+
+    #[test]
+    fn reads_characteristics() {
+        let bytes = TestImage::new("sample.dll").build();
+        let image = Image::from_file(&bytes).unwrap();
+        let text = &image.section_headers()[0];
+        assert!(text.contains_code() && text.is_executable() && text.is_readable());
+        assert!(!text.is_writable() && !text.is_discardable() && !text.is_shared());
+        assert!(!text.contains_initialized_data() && !text.contains_uninitialized_data());
+        assert!(text.is_code_section());
+        assert!(!text.is_vmt_section() && !text.is_data_section());
+        assert_eq!(text.alignment(), None);
+
+        let data = |name: &[u8], characteristics: u32| {
+            TestImage::new("sample.dll")
+                .section_name(name)
+                .section_characteristics(characteristics)
+                .build()
+        };
+        let bytes = data(b".rdata", 0x4000_0040);
+        let image = Image::from_file(&bytes).unwrap();
+        let rdata = &image.section_headers()[0];
+        assert!(rdata.is_vmt_section() && rdata.contains_initialized_data());
+        assert!(!rdata.is_writable() && !rdata.is_code_section());
+
+        let bytes = data(b".data", 0xc000_0040);
+        let image = Image::from_file(&bytes).unwrap();
+        let section = &image.section_headers()[0];
+        assert!(section.is_data_section() && section.is_writable());
+        assert!(section.has_characteristic(ImageSection::MemWrite));
+        assert!(!section.has_characteristic(ImageSection::MemExecute));
+
+        // `IMAGE_SCN_ALIGN_16BYTES`, only meaningful in object files.
+        let bytes = data(b".text$mn", 0x6050_0020);
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.section_headers()[0].alignment(), Some(16));
+        let bytes = data(b".text$mn", 0x60f0_0020);
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.section_headers()[0].alignment(), None);
+    }
+
+    #[test]
+    fn zero_fills_the_tail_past_the_raw_data() {
+        // 0x800 bytes at file offset 0x400 back a 0x2000 byte section.
+        let test_image = TestImage::new("sample.dll")
+            .raw_data(0x400, 0x800)
+            .contents(0x17f8, b"raw end!");
+        let file = test_image.build();
+        let mapped = test_image.build_mapped();
+        let file = Image::from_file(&file).unwrap();
+        let mapped = Image::from_mapped(&mapped).unwrap();
+
+        let section = &file.section_headers()[0];
+        assert_eq!(section.virtual_size(), 0x2000);
+        assert_eq!(section.size_of_raw_data(), 0x800);
+        assert_eq!(section.zero_fill_size(), 0x1800);
+
+        let raw = section.data(&file).unwrap();
+        assert_eq!(raw.len(), 0x800);
+        assert!(raw.ends_with(b"raw end!"));
+
+        let whole = section.data(&mapped).unwrap();
+        assert_eq!(whole.len(), 0x2000);
+        assert_eq!(&whole[..0x800], raw);
+        assert!(whole[0x800..].iter().all(|byte| byte.eq(&0)));
+    }
+
+    #[test]
+    fn translates_only_rvas_backed_by_raw_data() {
+        let bytes = TestImage::new("sample.dll").raw_data(0x400, 0x800).build();
+        let image = Image::from_file(&bytes).unwrap();
+        let section = &image.section_headers()[0];
+
+        assert_eq!(section.rva_to_offset(0x1000), Some(0x400));
+        assert_eq!(section.rva_to_offset(0x17ff), Some(0xbff));
+        // The zero-filled tail is mapped but not in the file.
+        assert!(section.contains_rva(0x1800));
+        assert_eq!(section.rva_to_offset(0x1800), None);
+        assert!(!section.contains_rva(0x3000));
+        assert_eq!(section.rva_to_offset(0xfff), None);
+
+        assert_eq!(section.offset_to_rva(0x400), Some(0x1000));
+        assert_eq!(section.offset_to_rva(0xbff), Some(0x17ff));
+        assert!(!section.contains_offset(0xc00));
+        assert_eq!(section.offset_to_rva(0xc00), None);
+
+        assert_eq!(image.offset_of_rva(0x17ff), Some(0xbff));
+        assert_eq!(image.offset_of_rva(0x1800), None);
     }
 }
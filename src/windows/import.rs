@@ -0,0 +1,224 @@
+use core::{
+    ffi::c_void,
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    mem::{size_of, transmute_copy},
+    ptr::null_mut,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use super::{ModuleSource, PebModules};
+
+/// An export of a loaded module, resolved on first use and cached. `F` is
+/// the function pointer type the export is called through.
+pub struct Import<F> {
+    module: &'static str,
+    name: &'static str,
+    address: AtomicPtr<c_void>,
+    marker: PhantomData<F>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportError {
+    ModuleNotFound {
+        module: &'static str,
+    },
+    ExportNotFound {
+        module: &'static str,
+        name: &'static str,
+    },
+}
+
+impl<F: Copy> Import<F> {
+    pub const fn new(module: &'static str, name: &'static str) -> Self {
+        Self {
+            module,
+            name,
+            address: AtomicPtr::new(null_mut()),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn module(self: &Self) -> &'static str {
+        self.module
+    }
+
+    pub fn name(self: &Self) -> &'static str {
+        self.name
+    }
+
+    /// Resolves the export through the loader list the first time and
+    /// returns the cached address afterwards. Failures are not cached, so a
+    /// module loaded later is still picked up.
+    pub fn address(self: &Self) -> Result<*const c_void, ImportError> {
+        let address = self.address.load(Ordering::Relaxed);
+        if !address.is_null() {
+            return Ok(address);
+        }
+
+        let module =
+            PebModules
+                .resolve_module(self.module, None)
+                .ok_or(ImportError::ModuleNotFound {
+                    module: self.module,
+                })?;
+        let address = module.export_function(&PebModules, self.name);
+        if address.is_null() {
+            return Err(ImportError::ExportNotFound {
+                module: self.module,
+                name: self.name,
+            });
+        }
+        self.address
+            .store(address as *mut c_void, Ordering::Relaxed);
+        Ok(address)
+    }
+
+    pub fn get(self: &Self) -> Result<F, ImportError> {
+        const { assert!(size_of::<F>() == size_of::<*const c_void>()) };
+        let address = self.address()?;
+        Ok(unsafe { transmute_copy::<*const c_void, F>(&address) })
+    }
+}
+
+impl Display for ImportError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::ModuleNotFound { module } => {
+                write!(f, "cannot import from {}: module is not loaded", module)
+            }
+            ImportError::ExportNotFound { module, name } => {
+                write!(f, "cannot import {}!{}: no such export", module, name)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ImportError {}
+
+/// Declares `unsafe extern "system"` functions that are looked up in a
+/// loaded module on their first call:
+///
+/// ```no_run
+/// # use core::ffi::c_void;
+/// # use pe_exports::import;
+/// import!(ntdll, fn NtClose(handle: *mut c_void) -> i32);
+/// import!(kernel32, pub fn Sleep(milliseconds: u32); fn GetTickCount() -> u32);
+/// ```
+///
+/// Each declaration becomes an `unsafe fn` with the same signature that
+/// panics with the module and export name if either cannot be found. Use
+/// [`Import`] directly to handle that case instead.
+#[macro_export]
+macro_rules! import {
+    ($module:ident, $($rest:tt)+) => {
+        $crate::import!(stringify!($module), $($rest)+);
+    };
+    ($module:expr, $(
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($($argument:ident: $type:ty),* $(,)?) $(-> $return:ty)?
+    );+ $(;)?) => {
+        $(
+            $(#[$meta])*
+            #[allow(non_snake_case)]
+            $vis unsafe fn $name($($argument: $type),*) $(-> $return)? {
+                static IMPORT: $crate::windows::Import<
+                    unsafe extern "system" fn($($type),*) $(-> $return)?,
+                > = $crate::windows::Import::new($module, stringify!($name));
+                match IMPORT.get() {
+                    Ok(function) => function($($argument),*),
+                    Err(error) => panic!("{}", error),
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(windows)]
+    use core::sync::atomic::Ordering;
+
+    use super::{Import, ImportError};
+
+    mod identifier {
+        crate::import!(kernel32, fn GetTickCount() -> u32);
+
+        /// Calling would need the loader list; taking the function checks
+        /// the expanded signature.
+        #[test]
+        fn expands_to_a_function() {
+            let _: unsafe fn() -> u32 = GetTickCount;
+        }
+    }
+
+    mod literal {
+        crate::import! {
+            "kernel32.dll",
+            /// Sleeps.
+            pub fn Sleep(milliseconds: u32);
+            pub(crate) fn GetProcAddress(
+                module: *mut core::ffi::c_void,
+                name: *const u8,
+            ) -> *const core::ffi::c_void;
+        }
+
+        #[test]
+        fn expands_every_function() {
+            let _: unsafe fn(u32) = Sleep;
+            let _: unsafe fn(*mut core::ffi::c_void, *const u8) -> *const core::ffi::c_void =
+                GetProcAddress;
+        }
+    }
+
+    #[test]
+    fn describes_failures() {
+        let import = Import::<unsafe extern "system" fn()>::new("kernel32.dll", "Missing");
+        assert_eq!(import.module(), "kernel32.dll");
+        assert_eq!(import.name(), "Missing");
+
+        let module_not_found = ImportError::ModuleNotFound { module: "missing" };
+        assert_eq!(
+            alloc::format!("{}", module_not_found),
+            "cannot import from missing: module is not loaded"
+        );
+        let export_not_found = ImportError::ExportNotFound {
+            module: "kernel32",
+            name: "Missing",
+        };
+        assert_eq!(
+            alloc::format!("{}", export_not_found),
+            "cannot import kernel32!Missing: no such export"
+        );
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn caches_resolved_exports() {
+        static GET_TICK_COUNT: Import<unsafe extern "system" fn() -> u32> =
+            Import::new("kernel32", "GetTickCount");
+        assert!(GET_TICK_COUNT.address.load(Ordering::Relaxed).is_null());
+
+        let first = GET_TICK_COUNT.address().unwrap();
+        assert!(!first.is_null());
+        assert_eq!(
+            GET_TICK_COUNT.address.load(Ordering::Relaxed),
+            first as *mut _
+        );
+        assert_eq!(GET_TICK_COUNT.address(), Ok(first));
+
+        let get_tick_count = GET_TICK_COUNT.get().unwrap();
+        assert_eq!(get_tick_count as *const core::ffi::c_void, first);
+        unsafe { get_tick_count() };
+
+        static MISSING: Import<unsafe extern "system" fn()> = Import::new("kernel32", "Missing");
+        assert_eq!(
+            MISSING.address(),
+            Err(ImportError::ExportNotFound {
+                module: "kernel32",
+                name: "Missing",
+            })
+        );
+    }
+}
@@ -0,0 +1,721 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::DemangledName;
+
+/// Undecorates Itanium C++ ABI names as emitted by MinGW, printing them the
+/// way `c++filt` does.
+pub struct ItaniumDemangler<'s> {
+    symbol: &'s [u8],
+    position: usize,
+    substitutions: Vec<String>,
+    template_arguments: Vec<String>,
+    depth: usize,
+}
+
+struct Name {
+    name: String,
+    is_template: bool,
+    is_structor: bool,
+    qualifiers: String,
+}
+
+impl<'s> ItaniumDemangler<'s> {
+    /// Nested types, template arguments and thunks recurse; bound them so
+    /// hostile symbols cannot exhaust the stack.
+    const MAX_DEPTH: usize = 256;
+
+    pub fn demangle(symbol: &'s str) -> Option<DemangledName> {
+        // 32-bit MinGW adds the usual C underscore in front of `_Z`.
+        let mangled = symbol
+            .strip_prefix("__Z")
+            .or_else(|| symbol.strip_prefix("_Z"))?;
+        // Suffixes such as `.constprop.0` name compiler generated clones.
+        let (mangled, clone_suffix) = match mangled.find('.') {
+            Some(dot) => mangled.split_at(dot),
+            None => (mangled, ""),
+        };
+        let mut demangler = Self {
+            symbol: mangled.as_bytes(),
+            position: 0,
+            substitutions: Vec::new(),
+            template_arguments: Vec::new(),
+            depth: 0,
+        };
+        let (name, signature) = demangler.encoding()?;
+        if demangler.position.ne(&demangler.symbol.len()) {
+            return None;
+        }
+        let signature = match clone_suffix.is_empty() {
+            true => signature,
+            false => format!("{} [clone {}]", signature, clone_suffix),
+        };
+        Some(DemangledName::new(name, signature))
+    }
+
+    fn peek(self: &Self) -> Option<u8> {
+        self.symbol.get(self.position).cloned()
+    }
+
+    fn next(self: &mut Self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn consume(self: &mut Self, prefix: &[u8]) -> bool {
+        let matches = self.symbol[self.position..].starts_with(prefix);
+        if matches {
+            self.position += prefix.len();
+        }
+        matches
+    }
+
+    fn expect(self: &mut Self, byte: u8) -> Option<()> {
+        self.next()?.eq(&byte).then_some(())
+    }
+
+    fn nested<T>(self: &mut Self, parse: fn(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth.ge(&ItaniumDemangler::MAX_DEPTH) {
+            return None;
+        }
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        parsed
+    }
+
+    fn encoding(self: &mut Self) -> Option<(String, String)> {
+        self.nested(Self::nested_encoding)
+    }
+
+    fn nested_encoding(self: &mut Self) -> Option<(String, String)> {
+        let special = [
+            (b"TV", "vtable for "),
+            (b"TI", "typeinfo for "),
+            (b"TS", "typeinfo name for "),
+            (b"TT", "VTT for "),
+        ];
+        for (prefix, description) in special {
+            if self.consume(prefix) {
+                let name = self.type_()?;
+                let signature = format!("{}{}", description, name);
+                return Some((name, signature));
+            }
+        }
+        for (prefix, description) in [
+            (b"Th", "non-virtual thunk to "),
+            (b"Tv", "virtual thunk to "),
+        ] {
+            if self.consume(prefix) {
+                self.call_offset(prefix[1])?;
+                let (name, signature) = self.encoding()?;
+                return Some((name, format!("{}{}", description, signature)));
+            }
+        }
+        if self.consume(b"GV") {
+            let name = self.name(false)?.name;
+            let signature = format!("guard variable for {}", name);
+            return Some((name, signature));
+        }
+
+        let name = self.name(true)?;
+        if self.peek().is_none() {
+            return Some((name.name.clone(), name.name));
+        }
+
+        // Only function templates encode their return type.
+        let return_type = match name.is_template && !name.is_structor {
+            true => Some(self.type_()?),
+            false => None,
+        };
+        let mut parameters = Vec::new();
+        while self.peek().is_some() {
+            parameters.push(self.type_()?);
+        }
+        if parameters.len().eq(&1) && parameters[0].eq("void") {
+            parameters.clear();
+        }
+
+        let mut signature = String::new();
+        if let Some(return_type) = return_type {
+            signature.push_str(&return_type);
+            signature.push(' ');
+        }
+        signature.push_str(&format!(
+            "{}({}){}",
+            name.name,
+            parameters.join(", "),
+            name.qualifiers
+        ));
+        Some((name.name, signature))
+    }
+
+    /// The `this` adjustment of a thunk: `h <offset> _` for non-virtual
+    /// thunks and `v <offset> _ <virtual offset> _` for virtual ones.
+    fn call_offset(self: &mut Self, kind: u8) -> Option<()> {
+        let count = match kind {
+            b'h' => 1,
+            _ => 2,
+        };
+        for _ in 0..count {
+            self.consume(b"n");
+            while self.peek()?.is_ascii_digit() {
+                self.position += 1;
+            }
+            self.expect(b'_')?;
+        }
+        Some(())
+    }
+
+    /// `record` marks the name of the function being encoded, whose
+    /// template arguments `T_` parameters refer to.
+    fn name(self: &mut Self, record: bool) -> Option<Name> {
+        match self.peek()? {
+            b'N' => self.nested_name(record),
+            b'Z' => None,
+            _ => {
+                let mut name = match self.consume(b"St") {
+                    true => format!("std::{}", self.unqualified_name(None)?),
+                    false => match self.peek()? {
+                        b'S' => self.substitution()?,
+                        _ => self.unqualified_name(None)?,
+                    },
+                };
+                let is_template = self.peek().eq(&Some(b'I'));
+                if is_template {
+                    self.substitutions.push(name.clone());
+                    self.push_template_arguments(&mut name, record)?;
+                }
+                Some(Name {
+                    name,
+                    is_template,
+                    is_structor: false,
+                    qualifiers: String::new(),
+                })
+            }
+        }
+    }
+
+    fn nested_name(self: &mut Self, record: bool) -> Option<Name> {
+        self.expect(b'N')?;
+        let mut qualifiers = String::new();
+        loop {
+            match self.peek()? {
+                b'r' => qualifiers.push_str(" restrict"),
+                b'V' => qualifiers.push_str(" volatile"),
+                b'K' => qualifiers.push_str(" const"),
+                _ => break,
+            }
+            self.position += 1;
+        }
+        if self.consume(b"R") {
+            qualifiers.push_str(" &");
+        } else if self.consume(b"O") {
+            qualifiers.push_str(" &&");
+        }
+
+        // Every prefix of the name but the complete one is a substitution
+        // candidate, except for `std` and prefixes that are substitutions
+        // already.
+        let mut prefix = String::new();
+        let mut last_component = String::new();
+        let mut is_template = false;
+        let mut is_structor = false;
+        while !self.consume(b"E") {
+            is_template = false;
+            match self.peek()? {
+                b'S' if prefix.is_empty() => {
+                    prefix = match self.consume(b"St") {
+                        true => String::from("std"),
+                        false => self.substitution()?,
+                    };
+                    last_component = prefix
+                        .split('<')
+                        .next()
+                        .and_then(|name| name.rsplit("::").next())
+                        .unwrap_or("")
+                        .to_string();
+                    continue;
+                }
+                b'I' => {
+                    is_template = true;
+                    self.push_template_arguments(&mut prefix, record)?;
+                }
+                b'L' => {
+                    self.position += 1;
+                    continue;
+                }
+                _ => {
+                    // Template arguments may follow a constructor, which
+                    // still has no return type.
+                    is_structor = matches!(self.peek(), Some(b'C' | b'D'));
+                    let component = self.unqualified_name(Some(&last_component))?;
+                    if !is_structor {
+                        last_component = component.clone();
+                    }
+                    prefix = match prefix.is_empty() {
+                        true => component,
+                        false => format!("{}::{}", prefix, component),
+                    };
+                }
+            }
+            if self.peek().ne(&Some(b'E')) {
+                self.substitutions.push(prefix.clone());
+            }
+        }
+        Some(Name {
+            name: prefix,
+            is_template,
+            is_structor,
+            qualifiers,
+        })
+    }
+
+    fn structor(self: &mut Self, class_name: &str) -> Option<String> {
+        let class_name = class_name.split(['<', '[']).next().unwrap_or(class_name);
+        match (self.next()?, self.next()?) {
+            (b'C', b'1'..=b'5') => Some(class_name.to_string()),
+            (b'D', b'0'..=b'5') => Some(format!("~{}", class_name)),
+            _ => None,
+        }
+    }
+
+    fn source_name(self: &mut Self) -> Option<String> {
+        let mut length = 0usize;
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            length = length.checked_mul(10)? + (digit - b'0') as usize;
+            self.position += 1;
+        }
+        let bytes = self
+            .symbol
+            .get(self.position..self.position.checked_add(length)?)?;
+        self.position += length;
+        let name = core::str::from_utf8(bytes).ok()?;
+        Some(match name.starts_with("_GLOBAL__N") {
+            true => String::from("(anonymous namespace)"),
+            false => name.to_string(),
+        })
+    }
+
+    fn unqualified_name(self: &mut Self, class_name: Option<&str>) -> Option<String> {
+        let mut name = match self.peek()? {
+            b'0'..=b'9' => self.source_name()?,
+            b'C' | b'D' => self.structor(class_name?)?,
+            b'a'..=b'z' => self.operator_name()?,
+            _ => return None,
+        };
+        while self.consume(b"B") {
+            name.push_str(&format!("[abi:{}]", self.source_name()?));
+        }
+        Some(name)
+    }
+
+    fn operator_name(self: &mut Self) -> Option<String> {
+        let code = [self.next()?, self.next()?];
+        let operator = match &code {
+            b"nw" => "operator new",
+            b"na" => "operator new[]",
+            b"dl" => "operator delete",
+            b"da" => "operator delete[]",
+            b"ps" | b"pl" => "operator+",
+            b"ng" | b"mi" => "operator-",
+            b"ad" | b"an" => "operator&",
+            b"de" | b"ml" => "operator*",
+            b"co" => "operator~",
+            b"dv" => "operator/",
+            b"rm" => "operator%",
+            b"or" => "operator|",
+            b"eo" => "operator^",
+            b"aS" => "operator=",
+            b"pL" => "operator+=",
+            b"mI" => "operator-=",
+            b"mL" => "operator*=",
+            b"dV" => "operator/=",
+            b"rM" => "operator%=",
+            b"aN" => "operator&=",
+            b"oR" => "operator|=",
+            b"eO" => "operator^=",
+            b"ls" => "operator<<",
+            b"rs" => "operator>>",
+            b"lS" => "operator<<=",
+            b"rS" => "operator>>=",
+            b"eq" => "operator==",
+            b"ne" => "operator!=",
+            b"lt" => "operator<",
+            b"gt" => "operator>",
+            b"le" => "operator<=",
+            b"ge" => "operator>=",
+            b"ss" => "operator<=>",
+            b"nt" => "operator!",
+            b"aa" => "operator&&",
+            b"oo" => "operator||",
+            b"pp" => "operator++",
+            b"mm" => "operator--",
+            b"cm" => "operator,",
+            b"pm" => "operator->*",
+            b"pt" => "operator->",
+            b"cl" => "operator()",
+            b"ix" => "operator[]",
+            b"qu" => "operator?",
+            b"cv" => return Some(format!("operator {}", self.type_()?)),
+            _ => return None,
+        };
+        Some(operator.to_string())
+    }
+
+    /// `S_`, `S<base 36>_` or one of the `std::` abbreviations, which are
+    /// spelled out in full like `c++filt` does.
+    fn substitution(self: &mut Self) -> Option<String> {
+        self.expect(b'S')?;
+        let abbreviation = match self.peek()? {
+            b'a' => "std::allocator",
+            b'b' => "std::basic_string",
+            b's' => "std::basic_string<char, std::char_traits<char>, std::allocator<char> >",
+            b'i' => "std::basic_istream<char, std::char_traits<char> >",
+            b'o' => "std::basic_ostream<char, std::char_traits<char> >",
+            b'd' => "std::basic_iostream<char, std::char_traits<char> >",
+            _ => {
+                let mut seq_id = None;
+                loop {
+                    let digit = match self.next()? {
+                        b'_' => break,
+                        digit @ b'0'..=b'9' => digit - b'0',
+                        letter @ b'A'..=b'Z' => letter - b'A' + 10,
+                        _ => return None,
+                    };
+                    seq_id = Some(seq_id.unwrap_or(0usize).checked_mul(36)? + digit as usize);
+                }
+                let index = seq_id.map_or(0, |seq_id| seq_id + 1);
+                return self.substitutions.get(index).cloned();
+            }
+        };
+        self.position += 1;
+        Some(abbreviation.to_string())
+    }
+
+    fn template_arguments(self: &mut Self, record: bool) -> Option<String> {
+        self.expect(b'I')?;
+        let mut arguments = Vec::new();
+        while !self.consume(b"E") {
+            arguments.push(self.template_argument()?);
+        }
+        if record {
+            self.template_arguments = arguments.clone();
+        }
+        let arguments = arguments.join(", ");
+        Some(match arguments.ends_with('>') {
+            true => format!("<{} >", arguments),
+            false => format!("<{}>", arguments),
+        })
+    }
+
+    /// Keeps `operator<` and its template arguments apart.
+    fn push_template_arguments(self: &mut Self, name: &mut String, record: bool) -> Option<()> {
+        if name.ends_with('<') {
+            name.push(' ');
+        }
+        name.push_str(&self.template_arguments(record)?);
+        Some(())
+    }
+
+    fn template_argument(self: &mut Self) -> Option<String> {
+        self.nested(Self::nested_template_argument)
+    }
+
+    fn nested_template_argument(self: &mut Self) -> Option<String> {
+        match self.peek()? {
+            b'L' => {
+                self.position += 1;
+                let literal_type = self.type_()?;
+                let negative = self.consume(b"n");
+                let start = self.position;
+                while self.peek().is_some_and(|byte| byte.ne(&b'E')) {
+                    self.position += 1;
+                }
+                let value = core::str::from_utf8(&self.symbol[start..self.position]).ok()?;
+                self.expect(b'E')?;
+                let sign = match negative {
+                    true => "-",
+                    false => "",
+                };
+                Some(match literal_type.as_str() {
+                    "bool" => match value {
+                        "0" => String::from("false"),
+                        _ => String::from("true"),
+                    },
+                    "int" => format!("{}{}", sign, value),
+                    "unsigned int" => format!("{}u", value),
+                    "long" => format!("{}{}l", sign, value),
+                    "unsigned long" => format!("{}ul", value),
+                    _ => format!("({}){}{}", literal_type, sign, value),
+                })
+            }
+            b'J' => {
+                self.position += 1;
+                let mut pack = Vec::new();
+                while !self.consume(b"E") {
+                    pack.push(self.template_argument()?);
+                }
+                Some(pack.join(", "))
+            }
+            b'X' => None,
+            _ => self.type_(),
+        }
+    }
+
+    fn type_(self: &mut Self) -> Option<String> {
+        self.nested(Self::nested_type)
+    }
+
+    fn nested_type(self: &mut Self) -> Option<String> {
+        let builtin = match self.peek()? {
+            b'v' => "void",
+            b'w' => "wchar_t",
+            b'b' => "bool",
+            b'c' => "char",
+            b'a' => "signed char",
+            b'h' => "unsigned char",
+            b's' => "short",
+            b't' => "unsigned short",
+            b'i' => "int",
+            b'j' => "unsigned int",
+            b'l' => "long",
+            b'm' => "unsigned long",
+            b'x' => "long long",
+            b'y' => "unsigned long long",
+            b'n' => "__int128",
+            b'o' => "unsigned __int128",
+            b'f' => "float",
+            b'd' => "double",
+            b'e' => "long double",
+            b'g' => "__float128",
+            b'z' => "...",
+            _ => "",
+        };
+        if !builtin.is_empty() {
+            self.position += 1;
+            return Some(builtin.to_string());
+        }
+
+        let type_ = match self.next()? {
+            b'P' => match self.peek()? {
+                b'F' => {
+                    self.position += 1;
+                    let (return_type, parameters) = self.function_type()?;
+                    self.substitutions
+                        .push(format!("{} ({})", return_type, parameters));
+                    format!("{} (*)({})", return_type, parameters)
+                }
+                _ => format!("{}*", self.type_()?),
+            },
+            b'R' => format!("{}&", self.type_()?),
+            b'O' => format!("{}&&", self.type_()?),
+            qualifier @ (b'K' | b'V' | b'r') => {
+                let mut qualifiers = String::new();
+                let mut qualifier = Some(qualifier);
+                while let Some(current) = qualifier {
+                    qualifiers.push_str(match current {
+                        b'K' => " const",
+                        b'V' => " volatile",
+                        _ => " restrict",
+                    });
+                    qualifier = match self.peek()? {
+                        next @ (b'K' | b'V' | b'r') => {
+                            self.position += 1;
+                            Some(next)
+                        }
+                        _ => None,
+                    };
+                }
+                format!("{}{}", self.type_()?, qualifiers)
+            }
+            b'F' => {
+                let (return_type, parameters) = self.function_type()?;
+                format!("{} ({})", return_type, parameters)
+            }
+            b'A' => {
+                let start = self.position;
+                while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+                    self.position += 1;
+                }
+                let extent = core::str::from_utf8(&self.symbol[start..self.position])
+                    .ok()?
+                    .to_string();
+                self.expect(b'_')?;
+                format!("{} [{}]", self.type_()?, extent)
+            }
+            b'M' => {
+                let class = self.type_()?;
+                match self.consume(b"F") {
+                    true => {
+                        let (return_type, parameters) = self.function_type()?;
+                        self.substitutions
+                            .push(format!("{} ({})", return_type, parameters));
+                        format!("{} ({}::*)({})", return_type, class, parameters)
+                    }
+                    false => format!("{} {}::*", self.type_()?, class),
+                }
+            }
+            b'T' => {
+                let mut index = 0usize;
+                loop {
+                    match self.next()? {
+                        b'_' => break,
+                        digit @ b'0'..=b'9' => index = index * 10 + (digit - b'0') as usize + 1,
+                        _ => return None,
+                    }
+                }
+                let parameter = self.template_arguments.get(index)?.clone();
+                match self.peek() {
+                    Some(b'I') => {
+                        self.substitutions.push(parameter.clone());
+                        format!("{}{}", parameter, self.template_arguments(false)?)
+                    }
+                    _ => parameter,
+                }
+            }
+            // Like the other builtin types these are not substitution candidates.
+            b'D' => {
+                return match self.next()? {
+                    b'n' => Some(String::from("decltype(nullptr)")),
+                    b's' => Some(String::from("char16_t")),
+                    b'i' => Some(String::from("char32_t")),
+                    b'u' => Some(String::from("char8_t")),
+                    b'p' => self.type_(),
+                    _ => None,
+                }
+            }
+            b'u' => self.source_name()?,
+            b'S' => {
+                self.position -= 1;
+                match self.consume(b"St") {
+                    true => {
+                        let name = format!("std::{}", self.unqualified_name(None)?);
+                        match self.peek() {
+                            Some(b'I') => {
+                                self.substitutions.push(name.clone());
+                                format!("{}{}", name, self.template_arguments(false)?)
+                            }
+                            _ => name,
+                        }
+                    }
+                    false => {
+                        let substitution = self.substitution()?;
+                        match self.peek() {
+                            Some(b'I') => {
+                                format!("{}{}", substitution, self.template_arguments(false)?)
+                            }
+                            _ => return Some(substitution),
+                        }
+                    }
+                }
+            }
+            b'N' | b'0'..=b'9' => {
+                self.position -= 1;
+                self.name(false)?.name
+            }
+            _ => return None,
+        };
+        self.substitutions.push(type_.clone());
+        Some(type_)
+    }
+
+    fn function_type(self: &mut Self) -> Option<(String, String)> {
+        let return_type = self.type_()?;
+        let mut parameters = Vec::new();
+        while !self.consume(b"E") {
+            parameters.push(self.type_()?);
+        }
+        if parameters.len().eq(&1) && parameters[0].eq("void") {
+            parameters.clear();
+        }
+        Some((return_type, parameters.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString};
+
+    use super::ItaniumDemangler;
+
+    fn signature(symbol: &str) -> Option<alloc::string::String> {
+        ItaniumDemangler::demangle(symbol).map(|name| name.signature().to_string())
+    }
+
+    #[test]
+    fn demangles_functions() {
+        assert_eq!(signature("_ZN3Bar3FooEi").as_deref(), Some("Bar::Foo(int)"));
+        assert_eq!(
+            signature("__ZN3Bar3FooEi").as_deref(),
+            Some("Bar::Foo(int)")
+        );
+        assert_eq!(signature("_Z1fPi").as_deref(), Some("f(int*)"));
+        assert_eq!(
+            signature("_Z4callPFiiE").as_deref(),
+            Some("call(int (*)(int))")
+        );
+        assert_eq!(
+            signature("_ZN2uiplERKNS_5PointES2_").as_deref(),
+            Some("ui::operator+(ui::Point const&, ui::Point const&)")
+        );
+    }
+
+    #[test]
+    fn demangles_structors_and_templates() {
+        let constructor = ItaniumDemangler::demangle("_ZN2ui6WidgetC2Ev").unwrap();
+        assert_eq!(constructor.name(), "ui::Widget::Widget");
+        assert_eq!(constructor.signature(), "ui::Widget::Widget()");
+        assert_eq!(
+            signature("_ZN2ui6WidgetD0Ev").as_deref(),
+            Some("ui::Widget::~Widget()")
+        );
+        assert_eq!(
+            signature("_ZNKSt6vectorIiSaIiEE3getEm").as_deref(),
+            Some("std::vector<int, std::allocator<int> >::get(unsigned long) const")
+        );
+        assert_eq!(
+            signature("_Z3maxIiET_S0_S0_").as_deref(),
+            Some("int max<int>(int, int)")
+        );
+    }
+
+    #[test]
+    fn demangles_special_names() {
+        assert_eq!(
+            signature("_ZTVN2ui6WidgetE").as_deref(),
+            Some("vtable for ui::Widget")
+        );
+        assert_eq!(
+            signature("_ZThn8_N2ui6Widget4drawEv").as_deref(),
+            Some("non-virtual thunk to ui::Widget::draw()")
+        );
+        assert_eq!(
+            signature("_Z5cloneRKSs.constprop.0").as_deref(),
+            Some("clone(std::basic_string<char, std::char_traits<char>, std::allocator<char> > const&) [clone .constprop.0]")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_symbols() {
+        assert_eq!(signature("Foo"), None);
+        assert_eq!(signature("_ZN3Bar3Foo"), None);
+        assert_eq!(signature("_Z1fPQ"), None);
+    }
+
+    #[test]
+    fn rejects_deeply_nested_symbols() {
+        let nested = format!("_Z1f{}i", "P".repeat(100));
+        assert!(signature(&nested).is_some());
+        let pointers = format!("_Z1f{}i", "P".repeat(5000));
+        assert_eq!(signature(&pointers), None);
+        let packs = format!("_Z1fI{}iE", "J".repeat(5000));
+        assert_eq!(signature(&packs), None);
+        let thunks = format!("_Z{}1fv", "Thn8_".repeat(5000));
+        assert_eq!(signature(&thunks), None);
+    }
+}
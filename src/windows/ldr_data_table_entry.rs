@@ -1,28 +1,97 @@
+#[cfg(any(feature = "alloc", test))]
+use alloc::vec::Vec;
 use core::{
-    ffi::{c_char, c_void, CStr},
+    ffi::c_void,
+    fmt::{self, Debug, Display, Formatter},
+    mem::offset_of,
+    ops::BitAnd,
     ptr::null,
-    slice::from_raw_parts,
 };
 
+#[cfg(any(feature = "alloc", test))]
+use super::PatternSet;
 use super::{
-    list_entry::ListEntryIterator, ImageDataDirectory, ImageDirectoryEntry, ImageDosHeader,
-    ImageExportDirectory, ImageNtHeaders, ImageOptionalHeader, ImageSectionHeaders, ListEntry, Peb,
-    UnicodeString,
+    list_entry::ListEntryIterator, ExportIterator, HashAlgorithm, Image, ImageDataDirectory,
+    ImageDirectoryEntry, ImageDosHeader, ImageExportDirectory, ImageNtHeaders, ImageOptionalHeader,
+    ImageSectionHeaders, LdrDdagNode, LdrLayout, ListEntry, LoaderOrder, Module, ModuleName,
+    NearestExport, Pattern, Peb, PebModules, ResolveError, ResolvedExport, SecurityReport,
+    UnicodeString, UnlinkedModule, Vtable,
 };
 
 #[repr(C)]
-#[derive(Debug)]
 pub struct LdrDataTableEntry {
     in_load_order_links: ListEntry,
     in_memory_order_links: ListEntry,
     in_initialization_order_links: ListEntry,
     dll_base: *const c_void,
-    reserved3: [*const c_void; 2],
+    entry_point: *const c_void,
+    size_of_image: u32,
     full_dll_name: UnicodeString,
     base_dll_name: UnicodeString,
-    reserved5: [*const c_void; 2],
-    check_sum: u32,
+    flags: u32,
+    obsolete_load_count: u16,
+    tls_index: u16,
+    hash_links: ListEntry,
     time_date_stamp: u32,
+    // Windows 8 and later from here on, see `LdrLayout`.
+    entry_point_activation_context: *const c_void,
+    lock: *const c_void,
+    ddag_node: *const LdrDdagNode,
+    node_module_link: ListEntry,
+    load_context: *const c_void,
+    parent_dll_base: *const c_void,
+    switch_back_context: *const c_void,
+    base_address_index_node: [*const c_void; 3],
+    mapping_info_index_node: [*const c_void; 3],
+    original_base: usize,
+    load_time: i64,
+    base_name_hash_value: u32,
+    load_reason: u32,
+}
+
+/// `LDRP_*` bits of `LdrDataTableEntry::flags`.
+#[repr(u32)]
+#[derive(Debug)]
+pub enum LdrEntryFlag {
+    PackagedBinary = 0x00000001,
+    MarkedForRemoval = 0x00000002,
+    ImageDll = 0x00000004,
+    LoadNotificationsSent = 0x00000008,
+    TelemetryEntryProcessed = 0x00000010,
+    ProcessStaticImport = 0x00000020,
+    InLegacyLists = 0x00000040,
+    InIndexes = 0x00000080,
+    ShimDll = 0x00000100,
+    InExceptionTable = 0x00000200,
+    LoadInProgress = 0x00001000,
+    LoadConfigProcessed = 0x00002000,
+    EntryProcessed = 0x00004000,
+    ProtectDelayLoad = 0x00008000,
+    DontCallForThreads = 0x00040000,
+    ProcessAttachCalled = 0x00080000,
+    ProcessAttachFailed = 0x00100000,
+    CorDeferredValidate = 0x00200000,
+    CorImage = 0x00400000,
+    DontRelocate = 0x00800000,
+    CorIlOnly = 0x01000000,
+    ChpeImage = 0x02000000,
+    Redirected = 0x10000000,
+    CompatDatabaseProcessed = 0x80000000,
+}
+
+/// `LDR_DLL_LOAD_REASON`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LdrDllLoadReason {
+    StaticDependency = 0,
+    StaticForwarderDependency = 1,
+    DynamicForwarderDependency = 2,
+    DelayloadDependency = 3,
+    DynamicLoad = 4,
+    AsImageLoad = 5,
+    AsDataLoad = 6,
+    EnclavePrimary = 7,
+    EnclaveDependency = 8,
+    Unknown = -1,
 }
 
 impl LdrDataTableEntry {
@@ -30,7 +99,29 @@ impl LdrDataTableEntry {
         &self.in_load_order_links
     }
 
-    #[allow(dead_code)]
+    pub fn links(self: &Self, order: LoaderOrder) -> &ListEntry {
+        match order {
+            LoaderOrder::Load => &self.in_load_order_links,
+            LoaderOrder::Memory => &self.in_memory_order_links,
+            LoaderOrder::Initialization => &self.in_initialization_order_links,
+        }
+    }
+
+    /// `CONTAINING_RECORD`: the entry whose `order` links are at `links`.
+    pub fn containing_record(
+        links: *const ListEntry,
+        order: LoaderOrder,
+    ) -> *const LdrDataTableEntry {
+        let offset = match order {
+            LoaderOrder::Load => offset_of!(LdrDataTableEntry, in_load_order_links),
+            LoaderOrder::Memory => offset_of!(LdrDataTableEntry, in_memory_order_links),
+            LoaderOrder::Initialization => {
+                offset_of!(LdrDataTableEntry, in_initialization_order_links)
+            }
+        };
+        links.wrapping_byte_sub(offset) as *const LdrDataTableEntry
+    }
+
     pub fn full_dll_name(self: &Self) -> &[u16] {
         self.full_dll_name.utf16()
     }
@@ -43,6 +134,118 @@ impl LdrDataTableEntry {
         self.dll_base
     }
 
+    pub fn entry_point(self: &Self) -> *const c_void {
+        self.entry_point
+    }
+
+    pub fn size_of_image(self: &Self) -> u32 {
+        self.size_of_image
+    }
+
+    pub fn flags(self: &Self) -> u32 {
+        self.flags
+    }
+
+    pub fn has_flag(self: &Self, flag: LdrEntryFlag) -> bool {
+        self.flags.bitand(flag as u32).ne(&0)
+    }
+
+    /// The reference count before Windows 8; `0xffff` for static imports
+    /// afterwards, see [`LdrDataTableEntry::load_count`].
+    pub fn obsolete_load_count(self: &Self) -> u16 {
+        self.obsolete_load_count
+    }
+
+    pub fn tls_index(self: &Self) -> u16 {
+        self.tls_index
+    }
+
+    pub fn hash_links(self: &Self) -> &ListEntry {
+        &self.hash_links
+    }
+
+    /// The fields after `time_date_stamp` exist from Windows 8 on.
+    fn has_windows8_layout(self: &Self) -> bool {
+        LdrLayout::current().ge(&LdrLayout::Windows8)
+    }
+
+    pub fn ddag_node(self: &Self) -> Option<&LdrDdagNode> {
+        if !self.has_windows8_layout() || self.ddag_node.is_null() {
+            return None;
+        }
+        Some(unsafe { &*self.ddag_node })
+    }
+
+    /// The reference count of the module, kept in its DDAG node since
+    /// Windows 8.
+    pub fn load_count(self: &Self) -> Option<u32> {
+        match self.has_windows8_layout() {
+            true => self
+                .ddag_node()
+                .map(|ddag_node| ddag_node.load_count().clone()),
+            false => Some(self.obsolete_load_count as u32),
+        }
+    }
+
+    pub fn parent_dll_base(self: &Self) -> Option<*const c_void> {
+        self.has_windows8_layout().then_some(self.parent_dll_base)
+    }
+
+    pub fn original_base(self: &Self) -> Option<usize> {
+        self.has_windows8_layout().then_some(self.original_base)
+    }
+
+    /// When the module was loaded, as a `FILETIME`.
+    pub fn load_time(self: &Self) -> Option<i64> {
+        self.has_windows8_layout().then_some(self.load_time)
+    }
+
+    /// `RtlHashUnicodeString` of the base name, which indexes the loader's
+    /// hash table.
+    pub fn base_name_hash_value(self: &Self) -> Option<u32> {
+        self.has_windows8_layout()
+            .then_some(self.base_name_hash_value)
+    }
+
+    pub fn load_reason(self: &Self) -> Option<LdrDllLoadReason> {
+        if !self.has_windows8_layout() {
+            return None;
+        }
+        Some(match self.load_reason {
+            0 => LdrDllLoadReason::StaticDependency,
+            1 => LdrDllLoadReason::StaticForwarderDependency,
+            2 => LdrDllLoadReason::DynamicForwarderDependency,
+            3 => LdrDllLoadReason::DelayloadDependency,
+            4 => LdrDllLoadReason::DynamicLoad,
+            5 => LdrDllLoadReason::AsImageLoad,
+            6 => LdrDllLoadReason::AsDataLoad,
+            7 => LdrDllLoadReason::EnclavePrimary,
+            8 => LdrDllLoadReason::EnclaveDependency,
+            _ => LdrDllLoadReason::Unknown,
+        })
+    }
+
+    pub fn time_date_stamp(self: &Self) -> u32 {
+        self.time_date_stamp
+    }
+
+    pub fn image(self: &Self) -> Image<'_> {
+        unsafe { Image::from_base(self.dll_base) }
+    }
+
+    pub fn module(self: &Self) -> Module<'_> {
+        Module::new(
+            ModuleName::Utf16(self.base_dll_name()),
+            ModuleName::Utf16(self.full_dll_name()),
+            self.dll_base,
+            self.image(),
+        )
+    }
+
+    pub fn security_report(self: &Self) -> SecurityReport<'_> {
+        self.image().security_report()
+    }
+
     pub fn dos_header(self: &Self) -> &ImageDosHeader {
         let dos_header = self.dll_base as *const ImageDosHeader;
         unsafe { &*dos_header }
@@ -52,8 +255,7 @@ impl LdrDataTableEntry {
         self.dos_header().nt_headers()
     }
 
-    #[allow(dead_code)]
-    pub fn optional_header(self: &Self) -> &ImageOptionalHeader {
+    pub fn optional_header(self: &Self) -> ImageOptionalHeader<'_> {
         self.nt_headers().optional_header()
     }
 
@@ -61,65 +263,46 @@ impl LdrDataTableEntry {
         self.nt_headers().section_headers()
     }
 
-    #[allow(dead_code)]
+    pub fn section_by_name(self: &Self, name: &str) -> Option<&ImageSectionHeaders> {
+        self.image().section_by_name(name)
+    }
+
     pub fn code_section(self: &Self) -> Option<&[u8]> {
-        for section in self.section_headers() {
-            if !section.is_code_section() {
-                continue;
-            }
-            return Some(unsafe {
-                from_raw_parts(
-                    self.dll_base
-                        .byte_offset(section.virtual_address().clone() as isize)
-                        as *const u8,
-                    section.virtual_size().clone() as usize,
-                )
-            });
-        }
-        None
+        self.image().code_section()
     }
 
-    #[allow(dead_code)]
     pub fn vmt_section(self: &Self) -> Option<&[u8]> {
-        for section in self.section_headers() {
-            if !section.is_vmt_section() {
-                continue;
-            }
-            return Some(unsafe {
-                from_raw_parts(
-                    self.dll_base
-                        .byte_offset(section.virtual_address().clone() as isize)
-                        as *const u8,
-                    section.virtual_size().clone() as usize,
-                )
-            });
-        }
-        None
+        self.image().vmt_section()
     }
 
-    #[allow(dead_code)]
     pub fn data_section(self: &Self) -> Option<&[u8]> {
-        for section in self.section_headers() {
-            if !section.is_data_section() {
-                continue;
-            }
-            return Some(unsafe {
-                from_raw_parts(
-                    self.dll_base
-                        .byte_offset(section.virtual_address().clone() as isize)
-                        as *const u8,
-                    section.virtual_size().clone() as usize,
-                )
-            });
-        }
-        None
+        self.image().data_section()
+    }
+
+    pub fn find_pattern(self: &Self, pattern: &Pattern) -> Option<u32> {
+        self.image().find_pattern(pattern)
+    }
+
+    pub fn find_all_pattern<'p>(
+        self: &Self,
+        pattern: &'p Pattern,
+    ) -> impl Iterator<Item = u32> + use<'_, 'p> {
+        self.image().find_all_pattern(pattern)
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub fn find_patterns(self: &Self, set: &PatternSet) -> Vec<Option<u32>> {
+        self.image().find_patterns(set)
+    }
+
+    pub fn find_vtable(self: &Self, class_name: &str) -> Option<Vtable<'_>> {
+        self.image().rtti().find_vtable(class_name)
     }
 
     pub fn data_directory(self: &Self, directory: ImageDirectoryEntry) -> &ImageDataDirectory {
         self.optional_header().data_directory(directory)
     }
 
-    #[allow(dead_code)]
     pub fn export_directory(self: &Self) -> &ImageExportDirectory {
         let virtual_address = self
             .data_directory(ImageDirectoryEntry::Export)
@@ -130,6 +313,10 @@ impl LdrDataTableEntry {
         }
     }
 
+    pub fn exports(self: &Self) -> ExportIterator<'_> {
+        self.image().exports()
+    }
+
     pub fn export_directory_from(
         self: &Self,
         data_directory: &ImageDataDirectory,
@@ -140,32 +327,66 @@ impl LdrDataTableEntry {
         }
     }
 
+    /// See [`ModuleName::has_same_base_dll_name`].
     pub fn has_same_base_dll_name(self: &Self, name: &str) -> bool {
-        let base_dll_name = self.base_dll_name();
-        let mut base_dll_name_iter = base_dll_name.iter();
-        let mut name_iter = name.as_bytes().iter();
-        let mut a = base_dll_name_iter.next();
-        let mut b = name_iter.next();
-        loop {
-            if a.is_none() || b.is_none() {
-                break;
-            }
-            let x = (unsafe { a.unwrap_unchecked() }.clone() as u8).to_ascii_lowercase();
-            let y = unsafe { b.unwrap_unchecked() }.to_ascii_lowercase();
-            if x.ne(&y) {
-                return false;
-            }
-            a = base_dll_name_iter.next();
-            b = name_iter.next();
-        }
-        true
+        ModuleName::Utf16(self.base_dll_name()).has_same_base_dll_name(name)
+    }
+
+    /// See [`ModuleName::has_same_full_dll_name`].
+    pub fn has_same_full_dll_name(self: &Self, path: &str) -> bool {
+        ModuleName::Utf16(self.full_dll_name()).has_same_full_dll_name(path)
     }
 
     pub fn iter() -> ListEntryIterator {
-        Peb::current().ldr().list().into_iter()
+        LdrDataTableEntry::iter_in(LoaderOrder::Load)
+    }
+
+    pub fn iter_in(order: LoaderOrder) -> ListEntryIterator {
+        Peb::current().ldr().iter(order)
+    }
+
+    /// See [`PebLdrData::unlinked_modules`](super::PebLdrData::unlinked_modules).
+    pub fn unlinked_modules() -> impl Iterator<Item = UnlinkedModule> {
+        let peb = Peb::current();
+        peb.ldr().unlinked_modules(peb.image_base_address())
+    }
+
+    /// Whether the entry is expected in the initialization order list of a
+    /// process whose image is at `image_base`. This is decided from the
+    /// loader's own bookkeeping rather than from the entry's links, which
+    /// unlinking a module may have cleared.
+    pub fn is_initialized(self: &Self, image_base: *const c_void) -> bool {
+        if self.dll_base.eq(&image_base) {
+            return false;
+        }
+        if matches!(
+            self.load_reason(),
+            Some(LdrDllLoadReason::AsImageLoad | LdrDllLoadReason::AsDataLoad)
+        ) {
+            return false;
+        }
+        self.has_flag(LdrEntryFlag::ProcessAttachCalled)
+            || match self.ddag_node() {
+                Some(ddag_node) => ddag_node.is_initialized(),
+                None => !self.has_windows8_layout() && self.has_flag(LdrEntryFlag::EntryProcessed),
+            }
+    }
+
+    /// The module whose `[dll_base, dll_base + size_of_image)` holds
+    /// `address`, e.g. a return address.
+    pub fn containing(address: *const c_void) -> Option<&'static LdrDataTableEntry> {
+        LdrDataTableEntry::iter().find(|entry| entry.contains_address(address))
+    }
+
+    pub fn contains_address(self: &Self, address: *const c_void) -> bool {
+        self.module().contains_address(address)
+    }
+
+    /// See [`Module::nearest_export`].
+    pub fn nearest_export(self: &Self, address: *const c_void) -> Option<NearestExport<'_>> {
+        self.module().nearest_export(address)
     }
 
-    #[allow(dead_code)]
     pub fn find(name: &str) -> Option<&LdrDataTableEntry> {
         let mut iter = LdrDataTableEntry::iter();
         loop {
@@ -182,76 +403,144 @@ impl LdrDataTableEntry {
         None
     }
 
-    pub fn export_function(self: &Self, name: &str) -> *const c_void {
-        let data_directory = self.data_directory(ImageDirectoryEntry::Export);
-        let export_directory = self.export_directory_from(data_directory);
+    /// Finds a module by its full path, e.g.
+    /// `C:\Windows\System32\kernel32.dll`.
+    pub fn find_by_full_dll_name(path: &str) -> Option<&'static LdrDataTableEntry> {
+        LdrDataTableEntry::iter().find(|entry| entry.has_same_full_dll_name(path))
+    }
 
-        for export_name_index in 0..export_directory.number_of_names().clone() {
-            if let Ok(export_name) = export_directory.name(self.dll_base, &export_name_index) {
-                if export_name.ne(name) {
-                    continue;
-                }
+    /// Finds a module by the hash of its lowercased base name, e.g.
+    /// `HashAlgorithm::Fnv1a64.hash_module_name("kernel32.dll")`.
+    pub fn find_by_hash(hash: u64, algorithm: HashAlgorithm) -> Option<&'static LdrDataTableEntry> {
+        LdrDataTableEntry::iter().find(|entry| {
+            algorithm
+                .hash_utf16_lowercase(entry.base_dll_name())
+                .eq(&hash)
+        })
+    }
 
-                let export_function_rva =
-                    export_directory.function_rva(self.dll_base, &export_name_index);
-                let export_function =
-                    unsafe { self.dll_base.byte_offset(export_function_rva as isize) };
+    pub fn export_function(self: &Self, name: &str) -> *const c_void {
+        self.resolve_export(name)
+            .map_or(null(), |resolved_export| resolved_export.address())
+    }
 
-                if data_directory.is_forwarded_export(export_function_rva) {
-                    if let Ok(forwarded_export) =
-                        unsafe { CStr::from_ptr(export_function as *const c_char) }.to_str()
-                    {
-                        return LdrDataTableEntry::resolve_forwarded_export(forwarded_export);
-                    }
-                }
+    /// Resolves an export by its biased ordinal, following forwarders.
+    pub fn export_by_ordinal(self: &Self, ordinal: u32) -> *const c_void {
+        self.resolve_export_by_ordinal(ordinal)
+            .map_or(null(), |resolved_export| resolved_export.address())
+    }
 
-                return export_function;
-            }
+    /// Like [`LdrDataTableEntry::export_function`], but takes the hash of the
+    /// export name instead of the name itself.
+    pub fn export_by_hash(self: &Self, hash: u64, algorithm: HashAlgorithm) -> *const c_void {
+        match self.image().export_by_hash(hash, algorithm) {
+            Some(export) => self
+                .follow_forwarders(export.rva().clone())
+                .map_or(null(), |resolved_export| resolved_export.address()),
+            None => null(),
         }
+    }
 
-        null()
-    }
-
-    fn resolve_forwarded_export(forwarded_export: &str) -> *const c_void {
-        let dot_pos = forwarded_export
-            .rfind('.')
-            .unwrap_or(forwarded_export.len());
-        let entry_name = &forwarded_export[..dot_pos];
-        let export_name = &forwarded_export[dot_pos + 1..];
-        match LdrDataTableEntry::find(entry_name) {
-            Some(entry) => entry.export_function(export_name),
-            None => {
-                for entry in LdrDataTableEntry::iter() {
-                    let non_forwarded_export = entry.non_forwarded_export_function(export_name);
-                    if !non_forwarded_export.is_null() {
-                        return non_forwarded_export;
-                    }
-                }
-                null()
-            }
+    /// Like [`LdrDataTableEntry::export_function`], but matches C++ exports
+    /// by their undecorated name, e.g. `Bar::Foo` for `?Foo@Bar@@QEAAXH@Z`.
+    #[cfg(any(feature = "demangle", test))]
+    pub fn export_function_by_undecorated_name(self: &Self, name: &str) -> *const c_void {
+        match self.image().export_by_undecorated_name(name) {
+            Some(export) => self
+                .follow_forwarders(export.rva().clone())
+                .map_or(null(), |resolved_export| resolved_export.address()),
+            None => null(),
         }
     }
 
-    fn non_forwarded_export_function(self: &Self, name: &str) -> *const c_void {
-        let data_directory = self.data_directory(ImageDirectoryEntry::Export);
-        let export_directory = self.export_directory_from(data_directory);
+    pub fn resolve_export(self: &Self, name: &str) -> Result<ResolvedExport<'_>, ResolveError<'_>> {
+        self.module().resolve_export(&PebModules, name)
+    }
+
+    pub fn resolve_export_by_ordinal(
+        self: &Self,
+        ordinal: u32,
+    ) -> Result<ResolvedExport<'_>, ResolveError<'_>> {
+        self.module()
+            .resolve_export_by_ordinal(&PebModules, ordinal)
+    }
+
+    /// See [`Module::follow_forwarders`]; forwarders are resolved through
+    /// the loader list.
+    pub fn follow_forwarders(
+        self: &Self,
+        rva: u32,
+    ) -> Result<ResolvedExport<'_>, ResolveError<'_>> {
+        self.module().follow_forwarders(&PebModules, rva)
+    }
+}
 
-        for export_name_index in 0..export_directory.number_of_names().clone() {
-            if let Ok(export_name) = export_directory.name(self.dll_base, &export_name_index) {
-                let export_function_rva =
-                    export_directory.function_rva(self.dll_base, &export_name_index);
+/// `kernel32.dll at 0x7ffb3a2c0000, size 0xc2000, timestamp 0x5f3a1b2c`.
+impl Display for LdrDataTableEntry {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {:p}, size {:#x}, timestamp {:#010x}",
+            self.base_dll_name, self.dll_base, self.size_of_image, self.time_date_stamp,
+        )
+    }
+}
 
-                if data_directory.is_forwarded_export(export_function_rva) {
-                    continue;
-                }
-                if export_name.ne(name) {
-                    continue;
-                }
+impl Debug for LdrDataTableEntry {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LdrDataTableEntry")
+            .field("base_dll_name", &self.base_dll_name)
+            .field("full_dll_name", &self.full_dll_name)
+            .field("dll_base", &self.dll_base)
+            .field("entry_point", &self.entry_point)
+            .field("size_of_image", &format_args!("{:#x}", self.size_of_image))
+            .field(
+                "time_date_stamp",
+                &format_args!("{:#010x}", self.time_date_stamp),
+            )
+            .finish_non_exhaustive()
+    }
+}
 
-                return unsafe { self.dll_base.byte_offset(export_function_rva as isize) };
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use core::mem::offset_of;
+
+    use super::LdrDataTableEntry;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn matches_the_64_bit_loader_layout() {
+        assert_eq!(offset_of!(LdrDataTableEntry, dll_base), 0x30);
+        assert_eq!(offset_of!(LdrDataTableEntry, size_of_image), 0x40);
+        assert_eq!(offset_of!(LdrDataTableEntry, base_dll_name), 0x58);
+        assert_eq!(offset_of!(LdrDataTableEntry, flags), 0x68);
+        assert_eq!(offset_of!(LdrDataTableEntry, obsolete_load_count), 0x6c);
+        assert_eq!(offset_of!(LdrDataTableEntry, time_date_stamp), 0x80);
+        assert_eq!(offset_of!(LdrDataTableEntry, ddag_node), 0x98);
+        assert_eq!(offset_of!(LdrDataTableEntry, parent_dll_base), 0xb8);
+        assert_eq!(offset_of!(LdrDataTableEntry, original_base), 0xf8);
+        assert_eq!(offset_of!(LdrDataTableEntry, load_time), 0x100);
+        assert_eq!(offset_of!(LdrDataTableEntry, base_name_hash_value), 0x108);
+        assert_eq!(offset_of!(LdrDataTableEntry, load_reason), 0x10c);
+    }
 
-        null()
+    /// `load_time` is 8-byte aligned on 32-bit Windows only, as in the
+    /// loader's own structure.
+    #[test]
+    #[cfg(all(target_pointer_width = "32", windows))]
+    fn matches_the_32_bit_loader_layout() {
+        assert_eq!(offset_of!(LdrDataTableEntry, dll_base), 0x18);
+        assert_eq!(offset_of!(LdrDataTableEntry, size_of_image), 0x20);
+        assert_eq!(offset_of!(LdrDataTableEntry, base_dll_name), 0x2c);
+        assert_eq!(offset_of!(LdrDataTableEntry, flags), 0x34);
+        assert_eq!(offset_of!(LdrDataTableEntry, obsolete_load_count), 0x38);
+        assert_eq!(offset_of!(LdrDataTableEntry, time_date_stamp), 0x44);
+        assert_eq!(offset_of!(LdrDataTableEntry, ddag_node), 0x50);
+        assert_eq!(offset_of!(LdrDataTableEntry, parent_dll_base), 0x60);
+        assert_eq!(offset_of!(LdrDataTableEntry, original_base), 0x80);
+        assert_eq!(offset_of!(LdrDataTableEntry, load_time), 0x88);
+        assert_eq!(offset_of!(LdrDataTableEntry, base_name_hash_value), 0x90);
+        assert_eq!(offset_of!(LdrDataTableEntry, load_reason), 0x94);
     }
 }
@@ -0,0 +1,83 @@
+use super::{memory_reader::read_value, ListEntry32, MemoryReader, UnicodeString32};
+
+/// The `LDR_DATA_TABLE_ENTRY` of a 32-bit process, read through a
+/// [`MemoryReader`]. Names stay in the target until read into a buffer.
+/// Only the fields every Windows version shares are modeled.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LdrDataTableEntry32 {
+    in_load_order_links: ListEntry32,
+    in_memory_order_links: ListEntry32,
+    in_initialization_order_links: ListEntry32,
+    dll_base: u32,
+    entry_point: u32,
+    size_of_image: u32,
+    full_dll_name: UnicodeString32,
+    base_dll_name: UnicodeString32,
+    flags: u32,
+    obsolete_load_count: u16,
+    tls_index: u16,
+    hash_links: ListEntry32,
+    time_date_stamp: u32,
+}
+
+impl LdrDataTableEntry32 {
+    /// `MAX_PATH`, enough for any base name.
+    pub const MAX_NAME_LENGTH: usize = 260;
+
+    pub fn read(reader: &(impl MemoryReader + ?Sized), address: u32) -> Option<Self> {
+        read_value(reader, address as u64)
+    }
+
+    pub fn list(self: &Self) -> &ListEntry32 {
+        &self.in_load_order_links
+    }
+
+    pub fn dll_base(self: &Self) -> &u32 {
+        &self.dll_base
+    }
+
+    pub fn entry_point(self: &Self) -> &u32 {
+        &self.entry_point
+    }
+
+    pub fn size_of_image(self: &Self) -> &u32 {
+        &self.size_of_image
+    }
+
+    pub fn flags(self: &Self) -> &u32 {
+        &self.flags
+    }
+
+    pub fn obsolete_load_count(self: &Self) -> &u16 {
+        &self.obsolete_load_count
+    }
+
+    pub fn tls_index(self: &Self) -> &u16 {
+        &self.tls_index
+    }
+
+    pub fn hash_links(self: &Self) -> &ListEntry32 {
+        &self.hash_links
+    }
+
+    pub fn time_date_stamp(self: &Self) -> &u32 {
+        &self.time_date_stamp
+    }
+
+    pub fn full_dll_name<'b>(
+        self: &Self,
+        reader: &(impl MemoryReader + ?Sized),
+        buffer: &'b mut [u16],
+    ) -> Option<&'b [u16]> {
+        self.full_dll_name.read(reader, buffer)
+    }
+
+    pub fn base_dll_name<'b>(
+        self: &Self,
+        reader: &(impl MemoryReader + ?Sized),
+        buffer: &'b mut [u16],
+    ) -> Option<&'b [u16]> {
+        self.base_dll_name.read(reader, buffer)
+    }
+}
@@ -0,0 +1,112 @@
+use core::ffi::c_void;
+
+use super::ListEntry;
+
+/// The head of an `LDR_DDAG_NODE`, the dependency graph node the loader
+/// keeps per module since Windows 8.
+#[repr(C)]
+#[derive(Debug)]
+pub struct LdrDdagNode {
+    modules: ListEntry,
+    service_tag_list: *const c_void,
+    load_count: u32,
+    load_while_unloading_count: u32,
+    lowest_link: u32,
+    dependencies: *const c_void,
+    incoming_dependencies: *const c_void,
+    state: i32,
+}
+
+/// `LDR_DDAG_STATE`, how far the loader got with a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LdrDdagState {
+    Merged = -5,
+    InitError = -4,
+    SnapError = -3,
+    Unloaded = -2,
+    Unloading = -1,
+    PlaceHolder = 0,
+    Mapping = 1,
+    Mapped = 2,
+    WaitingForDependencies = 3,
+    Snapping = 4,
+    Snapped = 5,
+    Condensed = 6,
+    ReadyToInit = 7,
+    Initializing = 8,
+    ReadyToRun = 9,
+    /// A state newer than this crate.
+    Unknown,
+}
+
+impl LdrDdagNode {
+    pub fn modules(self: &Self) -> &ListEntry {
+        &self.modules
+    }
+
+    pub fn load_count(self: &Self) -> &u32 {
+        &self.load_count
+    }
+
+    pub fn state(self: &Self) -> LdrDdagState {
+        match self.state {
+            -5 => LdrDdagState::Merged,
+            -4 => LdrDdagState::InitError,
+            -3 => LdrDdagState::SnapError,
+            -2 => LdrDdagState::Unloaded,
+            -1 => LdrDdagState::Unloading,
+            0 => LdrDdagState::PlaceHolder,
+            1 => LdrDdagState::Mapping,
+            2 => LdrDdagState::Mapped,
+            3 => LdrDdagState::WaitingForDependencies,
+            4 => LdrDdagState::Snapping,
+            5 => LdrDdagState::Snapped,
+            6 => LdrDdagState::Condensed,
+            7 => LdrDdagState::ReadyToInit,
+            8 => LdrDdagState::Initializing,
+            9 => LdrDdagState::ReadyToRun,
+            _ => LdrDdagState::Unknown,
+        }
+    }
+
+    /// Whether the loader has started running the module's initializers,
+    /// at which point it links the module into the initialization order
+    /// list.
+    pub fn is_initialized(self: &Self) -> bool {
+        matches!(
+            self.state(),
+            LdrDdagState::Initializing | LdrDdagState::ReadyToRun
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::{offset_of, zeroed};
+
+    use super::{LdrDdagNode, LdrDdagState};
+
+    #[test]
+    fn state_matches_the_loader_layout() {
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(offset_of!(LdrDdagNode, state), 0x38);
+        #[cfg(target_pointer_width = "32")]
+        assert_eq!(offset_of!(LdrDdagNode, state), 0x20);
+    }
+
+    #[test]
+    fn initialized_once_initializers_run() {
+        let mut ddag_node: LdrDdagNode = unsafe { zeroed() };
+        for (state, expected, initialized) in [
+            (-2, LdrDdagState::Unloaded, false),
+            (7, LdrDdagState::ReadyToInit, false),
+            (8, LdrDdagState::Initializing, true),
+            (9, LdrDdagState::ReadyToRun, true),
+            (42, LdrDdagState::Unknown, false),
+        ] {
+            ddag_node.state = state;
+            assert_eq!(ddag_node.state(), expected);
+            assert_eq!(ddag_node.is_initialized(), initialized);
+        }
+    }
+}
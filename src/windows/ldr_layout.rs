@@ -0,0 +1,27 @@
+use super::Peb;
+
+/// Which `LDR_DATA_TABLE_ENTRY` layout the loader uses. Fields after
+/// `TimeDateStamp` were reorganized in Windows 8, when the dependency graph
+/// (DDAG) replaced the per-entry load count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LdrLayout {
+    Windows7,
+    Windows8,
+}
+
+impl LdrLayout {
+    pub fn from_version(major: u32, minor: u32) -> Self {
+        match (major, minor).ge(&(6, 2)) {
+            true => LdrLayout::Windows8,
+            false => LdrLayout::Windows7,
+        }
+    }
+
+    pub fn current() -> Self {
+        let peb = Peb::current();
+        LdrLayout::from_version(
+            peb.os_major_version().clone(),
+            peb.os_minor_version().clone(),
+        )
+    }
+}
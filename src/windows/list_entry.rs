@@ -1,6 +1,4 @@
-use core::ptr::null;
-
-use super::LdrDataTableEntry;
+use super::{LdrDataTableEntry, LoaderOrder};
 
 #[repr(C)]
 #[derive(Debug)]
@@ -9,28 +7,39 @@ pub struct ListEntry {
     blink: *const ListEntry,
 }
 
+/// Walks the loader list with head `head`, mapping each link back to the
+/// entry that contains it.
 pub struct ListEntryIterator {
-    current: *const LdrDataTableEntry,
-    end: &'static LdrDataTableEntry,
+    head: *const ListEntry,
+    current: *const ListEntry,
+    order: LoaderOrder,
 }
 
 impl ListEntry {
+    pub fn flink(self: &Self) -> *const ListEntry {
+        self.flink
+    }
+
+    pub fn blink(self: &Self) -> *const ListEntry {
+        self.blink
+    }
+
+    /// The entry after this one in a load order list.
     pub fn next(self: &Self) -> &LdrDataTableEntry {
-        let next = self.flink as *const LdrDataTableEntry;
-        unsafe { &*next }
+        unsafe { &*LdrDataTableEntry::containing_record(self.flink, LoaderOrder::Load) }
     }
 
-    pub fn into_iter(self: &'static Self) -> ListEntryIterator {
-        ListEntryIterator::new(self)
+    pub fn into_iter(self: &'static Self, order: LoaderOrder) -> ListEntryIterator {
+        ListEntryIterator::new(self, order)
     }
 }
 
 impl ListEntryIterator {
-    pub fn new(list: &'static ListEntry) -> Self {
-        let begin = list.next();
+    pub fn new(head: &'static ListEntry, order: LoaderOrder) -> Self {
         Self {
-            current: begin,
-            end: begin,
+            head,
+            current: head.flink,
+            order,
         }
     }
 }
@@ -39,19 +48,11 @@ impl Iterator for ListEntryIterator {
     type Item = &'static LdrDataTableEntry;
 
     fn next(self: &mut Self) -> Option<Self::Item> {
-        if self.current.is_null() {
-            return None;
-        }
-        let current = unsafe { &*self.current };
-        if current.dll_base().is_null() {
+        if self.current.is_null() || self.current.eq(&self.head) {
             return None;
         }
-        let next = current.list().next() as *const LdrDataTableEntry;
-        let end = self.end as *const LdrDataTableEntry;
-        match next != end {
-            true => self.current = next,
-            false => self.current = null(),
-        }
+        let current = unsafe { &*LdrDataTableEntry::containing_record(self.current, self.order) };
+        self.current = current.links(self.order).flink;
         Some(current)
     }
 }
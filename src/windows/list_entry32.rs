@@ -0,0 +1,17 @@
+/// A `LIST_ENTRY32` of a 32-bit process.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ListEntry32 {
+    flink: u32,
+    blink: u32,
+}
+
+impl ListEntry32 {
+    pub fn flink(self: &Self) -> &u32 {
+        &self.flink
+    }
+
+    pub fn blink(self: &Self) -> &u32 {
+        &self.blink
+    }
+}
@@ -0,0 +1,15 @@
+/// The three lists the loader threads every `LdrDataTableEntry` onto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoaderOrder {
+    Load,
+    Memory,
+    Initialization,
+}
+
+impl LoaderOrder {
+    pub const ALL: [LoaderOrder; 3] = [
+        LoaderOrder::Load,
+        LoaderOrder::Memory,
+        LoaderOrder::Initialization,
+    ];
+}
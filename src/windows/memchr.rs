@@ -0,0 +1,200 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86 as arch;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64 as arch;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+static AVX2_SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const UNKNOWN: u8 = 0;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const SUPPORTED: u8 = 1;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const UNSUPPORTED: u8 = 2;
+
+/// Position of the first `needle` in `haystack`, using AVX2 or SSE2 when the
+/// CPU has them and a word-at-a-time search otherwise.
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if has_avx2() {
+            return unsafe { memchr_avx2(needle, haystack) };
+        }
+        #[cfg(any(
+            target_arch = "x86_64",
+            all(target_arch = "x86", target_feature = "sse2")
+        ))]
+        {
+            unsafe { memchr_sse2(needle, haystack) }
+        }
+        #[cfg(all(target_arch = "x86", not(target_feature = "sse2")))]
+        {
+            memchr_fallback(needle, haystack)
+        }
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        memchr_fallback(needle, haystack)
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn has_avx2() -> bool {
+    match AVX2_SUPPORT.load(Ordering::Relaxed) {
+        SUPPORTED => true,
+        UNSUPPORTED => false,
+        _ => {
+            let supported = unsafe { detect_avx2() };
+            let state = match supported {
+                true => SUPPORTED,
+                false => UNSUPPORTED,
+            };
+            AVX2_SUPPORT.store(state, Ordering::Relaxed);
+            supported
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe fn detect_avx2() -> bool {
+    const OSXSAVE: u32 = 1 << 27;
+    const AVX: u32 = 1 << 28;
+    const AVX2: u32 = 1 << 5;
+    const YMM_STATE: u64 = 0b110;
+
+    if arch::__get_cpuid_max(0).0 < 7 {
+        return false;
+    }
+    let features = arch::__cpuid(1);
+    if features.ecx & (OSXSAVE | AVX) != OSXSAVE | AVX {
+        return false;
+    }
+    // The OS has to save the upper halves of the YMM registers on context switches.
+    if arch::_xgetbv(0) & YMM_STATE != YMM_STATE {
+        return false;
+    }
+    arch::__cpuid_count(7, 0).ebx & AVX2 != 0
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn memchr_avx2(needle: u8, haystack: &[u8]) -> Option<usize> {
+    const LANES: usize = 32;
+    let needles = arch::_mm256_set1_epi8(needle as i8);
+    let mut offset = 0;
+    while offset + LANES <= haystack.len() {
+        let chunk = arch::_mm256_loadu_si256(haystack.as_ptr().add(offset) as *const _);
+        let mask = arch::_mm256_movemask_epi8(arch::_mm256_cmpeq_epi8(chunk, needles));
+        if mask != 0 {
+            return Some(offset + mask.trailing_zeros() as usize);
+        }
+        offset += LANES;
+    }
+    memchr_fallback(needle, &haystack[offset..]).map(|position| offset + position)
+}
+
+#[cfg(any(
+    target_arch = "x86_64",
+    all(target_arch = "x86", target_feature = "sse2")
+))]
+unsafe fn memchr_sse2(needle: u8, haystack: &[u8]) -> Option<usize> {
+    const LANES: usize = 16;
+    let needles = arch::_mm_set1_epi8(needle as i8);
+    let mut offset = 0;
+    while offset + LANES <= haystack.len() {
+        let chunk = arch::_mm_loadu_si128(haystack.as_ptr().add(offset) as *const _);
+        let mask = arch::_mm_movemask_epi8(arch::_mm_cmpeq_epi8(chunk, needles));
+        if mask != 0 {
+            return Some(offset + mask.trailing_zeros() as usize);
+        }
+        offset += LANES;
+    }
+    memchr_fallback(needle, &haystack[offset..]).map(|position| offset + position)
+}
+
+fn memchr_fallback(needle: u8, haystack: &[u8]) -> Option<usize> {
+    const WORD: usize = core::mem::size_of::<usize>();
+    const LOW_BITS: usize = usize::MAX / 0xff;
+    const HIGH_BITS: usize = LOW_BITS << 7;
+
+    let needles = LOW_BITS * needle as usize;
+    let mut chunks = haystack.chunks_exact(WORD);
+    let mut offset = 0;
+    for chunk in chunks.by_ref() {
+        let word = usize::from_ne_bytes(chunk.try_into().unwrap()) ^ needles;
+        if word.wrapping_sub(LOW_BITS) & !word & HIGH_BITS != 0 {
+            break;
+        }
+        offset += WORD;
+    }
+    haystack[offset..]
+        .iter()
+        .position(|byte| byte.eq(&needle))
+        .map(|position| offset + position)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+
+    /// Every search path, checked against a plain scan for each length up to
+    /// a few vector widths and each needle position, including none.
+    fn check(search: unsafe fn(u8, &[u8]) -> Option<usize>) {
+        for filler in [0x00, 0x7f, 0x80, 0xfe] {
+            let needle: u8 = 0xff;
+            for len in 0..100 {
+                let mut haystack = vec![filler; len];
+                assert_eq!(unsafe { search(needle, &haystack) }, None);
+                for position in 0..len {
+                    haystack.fill(filler);
+                    haystack[position] = needle;
+                    assert_eq!(unsafe { search(needle, &haystack) }, Some(position));
+                    // A later needle must not be reported instead.
+                    haystack.push(needle);
+                    assert_eq!(unsafe { search(needle, &haystack) }, Some(position));
+                    haystack.pop();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn memchr_matches_a_plain_scan() {
+        check(memchr);
+        check(memchr_fallback);
+    }
+
+    #[test]
+    fn memchr_handles_unaligned_starts() {
+        let haystack: Vec<u8> = (0..=255).collect();
+        for start in 0..64 {
+            for needle in [0u8, 63, 64, 100, 255] {
+                let expected = haystack[start..].iter().position(|byte| byte.eq(&needle));
+                assert_eq!(memchr(needle, &haystack[start..]), expected);
+                assert_eq!(memchr_fallback(needle, &haystack[start..]), expected);
+            }
+        }
+    }
+
+    #[cfg(any(
+        target_arch = "x86_64",
+        all(target_arch = "x86", target_feature = "sse2")
+    ))]
+    #[test]
+    fn memchr_sse2_matches_a_plain_scan() {
+        check(memchr_sse2);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn memchr_avx2_matches_a_plain_scan() {
+        if has_avx2() {
+            check(memchr_avx2);
+        }
+    }
+}
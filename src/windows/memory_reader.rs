@@ -0,0 +1,21 @@
+use core::{
+    mem::{size_of, MaybeUninit},
+    slice::from_raw_parts_mut,
+};
+
+/// Reads the memory of another process, e.g. through `ReadProcessMemory`,
+/// so its loader structures can be walked from the outside.
+pub trait MemoryReader {
+    /// Fills `buffer` with the bytes at `address`, failing if any of them
+    /// cannot be read.
+    fn read(self: &Self, address: u64, buffer: &mut [u8]) -> Option<()>;
+}
+
+/// Reads a `T` at `address`. Only used for the `#[repr(C)]` loader
+/// structures, which are plain integers and valid for any bytes.
+pub(crate) fn read_value<T: Copy, R: MemoryReader + ?Sized>(reader: &R, address: u64) -> Option<T> {
+    let mut value = MaybeUninit::<T>::zeroed();
+    let bytes = unsafe { from_raw_parts_mut(value.as_mut_ptr() as *mut u8, size_of::<T>()) };
+    reader.read(address, bytes)?;
+    Some(unsafe { value.assume_init() })
+}
@@ -1,29 +1,127 @@
+mod api_set_map;
+#[cfg(any(feature = "alloc", test))]
+mod class_hierarchy;
+#[cfg(any(feature = "demangle", test))]
+mod demangle;
+mod export;
+#[cfg(any(feature = "alloc", test))]
+mod export_cache;
+mod forwarder;
+mod hash_algorithm;
+mod image;
+mod image_arm64_runtime_function;
 mod image_data_directory;
+mod image_debug_directory;
 mod image_directory_entry;
 mod image_dos_header;
 mod image_export_directory;
 mod image_file_header;
+mod image_load_config_directory;
 mod image_nt_headers;
 mod image_optional_header;
 mod image_section_headers;
+mod import;
+#[cfg(any(feature = "demangle", test))]
+mod itanium_demangler;
 mod ldr_data_table_entry;
+mod ldr_data_table_entry32;
+mod ldr_ddag_node;
+mod ldr_layout;
 mod list_entry;
+mod list_entry32;
+mod loader_order;
+mod memchr;
+mod memory_reader;
+mod module;
+mod module_source;
+#[cfg(any(feature = "demangle", test))]
+mod msvc_demangler;
+mod nearest_export;
+mod pattern;
+#[cfg(any(feature = "alloc", test))]
+mod pattern_set;
 mod peb;
+mod peb32;
 mod peb_ldr_data;
+mod peb_ldr_data32;
+mod rich_header;
+mod rtti;
+mod rtti_base_class_descriptor;
+mod rtti_class_hierarchy_descriptor;
+mod rtti_complete_object_locator;
+mod rtti_type_descriptor;
+mod security_report;
+#[cfg(test)]
+mod test_image;
 mod unicode_string;
+mod unicode_string32;
+mod unlinked_module;
 
+pub use api_set_map::{
+    ApiSetEntry, ApiSetEntryIterator, ApiSetMap, ApiSetName, ApiSetValue, ApiSetValueIterator,
+};
+#[cfg(any(feature = "alloc", test))]
+pub use class_hierarchy::{BaseClass, ClassHierarchy, ClassInfo, ClassNode, UndecoratedClassName};
+#[cfg(any(feature = "demangle", test))]
+pub use demangle::{demangle, DemangledName};
+pub use export::{Export, ExportIterator};
+#[cfg(any(feature = "alloc", test))]
+pub use export_cache::{ExportCache, ExportKey};
+pub use forwarder::{
+    ForwardedExport, Forwarder, ForwarderChain, ResolveError, ResolveFailure, ResolvedExport,
+};
+pub use hash_algorithm::HashAlgorithm;
+pub use image::{Image, ImageLayout};
+pub use image_arm64_runtime_function::{
+    Arm64FrameChain, Arm64PackedUnwind, Arm64UnwindFlag, ImageArm64RuntimeFunction,
+};
 pub use image_data_directory::ImageDataDirectory;
+pub use image_debug_directory::{ImageCetCharacteristics, ImageDebugDirectory, ImageDebugType};
 pub use image_directory_entry::ImageDirectoryEntry;
 pub use image_dos_header::ImageDosHeader;
 pub use image_export_directory::ImageExportDirectory;
-pub use image_file_header::ImageFileHeader;
+pub use image_file_header::{ImageFile, ImageFileHeader, ImageMachine};
+pub use image_load_config_directory::{
+    ImageGuard, ImageLoadConfigCodeIntegrity, ImageLoadConfigDirectory, ImageLoadConfigDirectory32,
+    ImageLoadConfigDirectory64,
+};
 pub use image_nt_headers::ImageNtHeaders;
-pub use image_optional_header::ImageOptionalHeader;
+pub use image_optional_header::{
+    ImageDllCharacteristics, ImageOptionalHeader, ImageOptionalHeader32, ImageOptionalHeader64,
+};
 pub use image_section_headers::ImageSectionHeaders;
-pub use ldr_data_table_entry::LdrDataTableEntry;
-pub use list_entry::ListEntry;
+pub use import::{Import, ImportError};
+#[cfg(any(feature = "demangle", test))]
+pub use itanium_demangler::ItaniumDemangler;
+pub use ldr_data_table_entry::{LdrDataTableEntry, LdrDllLoadReason, LdrEntryFlag};
+pub use ldr_data_table_entry32::LdrDataTableEntry32;
+pub use ldr_ddag_node::{LdrDdagNode, LdrDdagState};
+pub use ldr_layout::LdrLayout;
+pub use list_entry::{ListEntry, ListEntryIterator};
+pub use list_entry32::ListEntry32;
+pub use loader_order::LoaderOrder;
+pub use memory_reader::MemoryReader;
+pub use module::{Module, ModuleName, ModuleNameUnits};
+pub use module_source::{ModuleList, ModuleSource, PebModules};
+#[cfg(any(feature = "demangle", test))]
+pub use msvc_demangler::MsvcDemangler;
+pub use nearest_export::NearestExport;
+pub use pattern::{Pattern, PatternMatches};
+#[cfg(any(feature = "alloc", test))]
+pub use pattern_set::{PatternSet, PatternSetMatches};
 pub use peb::Peb;
+pub use peb32::Peb32;
 pub use peb_ldr_data::PebLdrData;
+pub use peb_ldr_data32::{LdrDataTableEntry32Iterator, PebLdrData32};
+pub use rich_header::{RichEntry, RichEntryIterator, RichHeader};
+pub use rtti::{Rtti, Vtable};
+pub use rtti_base_class_descriptor::{RttiBaseClass, RttiBaseClassDescriptor, RttiPmd};
+pub use rtti_class_hierarchy_descriptor::{RttiClassHierarchyDescriptor, RttiInheritance};
+pub use rtti_complete_object_locator::RttiCompleteObjectLocator;
+pub use rtti_type_descriptor::RttiTypeDescriptor;
+pub use security_report::SecurityReport;
 pub use unicode_string::UnicodeString;
+pub use unicode_string32::UnicodeString32;
+pub use unlinked_module::UnlinkedModule;
 
 pub type PortableExecutable = LdrDataTableEntry;
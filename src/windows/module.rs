@@ -0,0 +1,467 @@
+use core::{
+    char::{decode_utf16, REPLACEMENT_CHARACTER},
+    ffi::c_void,
+    fmt::{self, Display, Formatter, Write},
+    iter::Cloned,
+    ptr::null,
+    slice::Iter,
+    str::EncodeUtf16,
+};
+
+use super::{
+    ForwardedExport, Forwarder, ForwarderChain, Image, ImageDirectoryEntry, ModuleSource,
+    NearestExport, ResolveError, ResolveFailure, ResolvedExport,
+};
+
+/// A module as seen by export resolution: its names, the address it is
+/// loaded at and its image. Modules of the loader list read the image at
+/// `dll_base`; modules of a [`ModuleList`](super::ModuleList) may read it
+/// from anywhere, e.g. a file, and only use `dll_base` for the addresses
+/// they resolve to.
+#[derive(Debug, Clone, Copy)]
+pub struct Module<'a> {
+    base_dll_name: ModuleName<'a>,
+    full_dll_name: ModuleName<'a>,
+    dll_base: *const c_void,
+    image: Image<'a>,
+}
+
+/// The loader stores module names as UTF-16, user supplied modules name
+/// them with a `str`.
+#[derive(Debug, Clone, Copy)]
+pub enum ModuleName<'a> {
+    Utf16(&'a [u16]),
+    Utf8(&'a str),
+}
+
+pub enum ModuleNameUnits<'a> {
+    Utf16(Cloned<Iter<'a, u16>>),
+    Utf8(EncodeUtf16<'a>),
+}
+
+impl<'a> Module<'a> {
+    pub fn new(
+        base_dll_name: ModuleName<'a>,
+        full_dll_name: ModuleName<'a>,
+        dll_base: *const c_void,
+        image: Image<'a>,
+    ) -> Self {
+        Self {
+            base_dll_name,
+            full_dll_name,
+            dll_base,
+            image,
+        }
+    }
+
+    /// A module named `name` that pretends to be loaded at `dll_base`, read
+    /// from the on-disk `bytes` of its image.
+    pub fn from_file(name: &'a str, dll_base: *const c_void, bytes: &'a [u8]) -> Option<Self> {
+        let name = ModuleName::Utf8(name);
+        Some(Module::new(name, name, dll_base, Image::from_file(bytes)?))
+    }
+
+    /// Like [`Module::from_file`], but `bytes` are laid out as mapped by the
+    /// loader.
+    pub fn from_mapped(name: &'a str, dll_base: *const c_void, bytes: &'a [u8]) -> Option<Self> {
+        let name = ModuleName::Utf8(name);
+        Some(Module::new(
+            name,
+            name,
+            dll_base,
+            Image::from_mapped(bytes)?,
+        ))
+    }
+
+    pub fn base_dll_name(self: &Self) -> &ModuleName<'a> {
+        &self.base_dll_name
+    }
+
+    pub fn full_dll_name(self: &Self) -> &ModuleName<'a> {
+        &self.full_dll_name
+    }
+
+    pub fn dll_base(self: &Self) -> *const c_void {
+        self.dll_base
+    }
+
+    pub fn image(self: &Self) -> &Image<'a> {
+        &self.image
+    }
+
+    /// The link time recorded in the image, which tells a module apart from
+    /// another one loaded at the same `dll_base` later.
+    pub fn time_date_stamp(self: &Self) -> u32 {
+        self.image.file_header().time_date_stamp()
+    }
+
+    pub fn size_of_image(self: &Self) -> u32 {
+        self.image.optional_header().size_of_image()
+    }
+
+    pub fn contains_address(self: &Self, address: *const c_void) -> bool {
+        ((address as usize).wrapping_sub(self.dll_base as usize) as u64)
+            .lt(&(self.size_of_image() as u64))
+    }
+
+    /// Describes `address` by the closest non-forwarded export at or before
+    /// it, e.g. `kernel32!Sleep+0x1c`, or `kernel32!#12+0x1c` when that
+    /// export has no name. `None` if `address` is not in this module.
+    pub fn nearest_export(self: &Self, address: *const c_void) -> Option<NearestExport<'a>> {
+        if !self.contains_address(address) {
+            return None;
+        }
+        let rva = (address as usize - self.dll_base as usize) as u32;
+        let data_directory = self.image.data_directory(ImageDirectoryEntry::Export);
+        let nearest = self
+            .image
+            .export_functions()
+            .filter(|(_, function_rva)| {
+                function_rva.le(&rva) && !data_directory.is_forwarded_export(function_rva.clone())
+            })
+            .fold(
+                None,
+                |nearest: Option<(u32, u32)>, function| match nearest {
+                    Some(nearest) if nearest.1.ge(&function.1) => Some(nearest),
+                    _ => Some(function),
+                },
+            );
+        Some(match nearest {
+            Some((ordinal, function_rva)) => {
+                // Prefer a name, which aliases at the same RVA may have.
+                let name = self
+                    .image
+                    .exports()
+                    .find(|export| export.rva().eq(&function_rva))
+                    .map(|export| export.name());
+                NearestExport::new(self.base_dll_name, name, Some(ordinal), rva - function_rva)
+            }
+            None => NearestExport::new(self.base_dll_name, None, None, rva),
+        })
+    }
+
+    pub fn export_function<S: ModuleSource + ?Sized>(
+        self: &Self,
+        source: &'a S,
+        name: &str,
+    ) -> *const c_void {
+        self.resolve_export(source, name)
+            .map_or(null(), |resolved_export| resolved_export.address())
+    }
+
+    pub fn resolve_export<S: ModuleSource + ?Sized>(
+        self: &Self,
+        source: &'a S,
+        name: &str,
+    ) -> Result<ResolvedExport<'a>, ResolveError<'a>> {
+        match self.export_rva(ForwardedExport::Name(name)) {
+            Some(rva) => self.follow_forwarders(source, rva),
+            None => Err(ResolveError::new(
+                ResolveFailure::ExportNotFound,
+                &ForwarderChain::new(),
+            )),
+        }
+    }
+
+    pub fn resolve_export_by_ordinal<S: ModuleSource + ?Sized>(
+        self: &Self,
+        source: &'a S,
+        ordinal: u32,
+    ) -> Result<ResolvedExport<'a>, ResolveError<'a>> {
+        match self.export_rva(ForwardedExport::Ordinal(ordinal)) {
+            Some(rva) => self.follow_forwarders(source, rva),
+            None => Err(ResolveError::new(
+                ResolveFailure::ExportNotFound,
+                &ForwarderChain::new(),
+            )),
+        }
+    }
+
+    fn export_rva(self: &Self, export: ForwardedExport) -> Option<u32> {
+        match export {
+            ForwardedExport::Name(name) => {
+                self.image.export(name).map(|export| export.rva().clone())
+            }
+            ForwardedExport::Ordinal(ordinal) => self.image.export_rva_by_ordinal(ordinal),
+        }
+    }
+
+    /// Resolves the export at `rva` of this module, following forwarders
+    /// into the modules of `source` they name. Forwarders are never guessed:
+    /// a target module that is not loaded fails the resolution, as do cycles
+    /// and chains longer than [`ForwarderChain::MAX_DEPTH`].
+    pub fn follow_forwarders<S: ModuleSource + ?Sized>(
+        self: &Self,
+        source: &'a S,
+        rva: u32,
+    ) -> Result<ResolvedExport<'a>, ResolveError<'a>> {
+        let mut chain = ForwarderChain::new();
+        let mut module = self.clone();
+        let mut rva = rva;
+        loop {
+            let data_directory = module.image.data_directory(ImageDirectoryEntry::Export);
+            if !data_directory.is_forwarded_export(rva) {
+                let address = module.dll_base.wrapping_byte_add(rva as usize);
+                return Ok(ResolvedExport::new(address, chain));
+            }
+
+            let failure = |failure, chain| Err(ResolveError::new(failure, chain));
+            let forwarder = match module.image.str_at_rva(rva) {
+                Some(forwarder) => forwarder,
+                None => return failure(ResolveFailure::MalformedForwarder, &chain),
+            };
+            if chain.contains(forwarder) {
+                return failure(ResolveFailure::Cycle, &chain);
+            }
+            if !chain.push(forwarder) {
+                return failure(ResolveFailure::TooDeep, &chain);
+            }
+
+            let forwarder = match Forwarder::parse(forwarder) {
+                Some(forwarder) => forwarder,
+                None => return failure(ResolveFailure::MalformedForwarder, &chain),
+            };
+            module = match source.resolve_module(forwarder.module(), Some(&module)) {
+                Some(module) => module,
+                None => return failure(ResolveFailure::ModuleNotLoaded, &chain),
+            };
+            rva = match module.export_rva(forwarder.export().clone()) {
+                Some(rva) => rva,
+                None => return failure(ResolveFailure::ExportNotFound, &chain),
+            };
+        }
+    }
+}
+
+impl<'a> ModuleName<'a> {
+    pub fn units(self: &Self) -> ModuleNameUnits<'a> {
+        match self {
+            ModuleName::Utf16(name) => ModuleNameUnits::Utf16(name.iter().cloned()),
+            ModuleName::Utf8(name) => ModuleNameUnits::Utf8(name.encode_utf16()),
+        }
+    }
+
+    /// The name without its extension, e.g. `kernel32` for `kernel32.dll`.
+    pub fn stem(self: &Self) -> ModuleName<'a> {
+        match self {
+            ModuleName::Utf16(name) => match name.iter().rposition(|unit| unit.eq(&0x2e)) {
+                Some(index) => ModuleName::Utf16(&name[..index]),
+                None => ModuleName::Utf16(name),
+            },
+            ModuleName::Utf8(name) => match name.rfind('.') {
+                Some(index) => ModuleName::Utf8(&name[..index]),
+                None => ModuleName::Utf8(name),
+            },
+        }
+    }
+
+    pub fn chars(self: &Self) -> impl Iterator<Item = char> + use<'a> {
+        decode_utf16(self.units()).map(|character| character.unwrap_or(REPLACEMENT_CHARACTER))
+    }
+
+    /// Compares the names by their Unicode lowercase mapping, without
+    /// allocating.
+    pub fn eq_ignore_case(self: &Self, other: &ModuleName) -> bool {
+        self.chars()
+            .flat_map(char::to_lowercase)
+            .eq(other.chars().flat_map(char::to_lowercase))
+    }
+
+    /// The name without a trailing `.dll`, if it has one.
+    pub fn strip_dll_extension(self: &Self) -> Option<ModuleName<'a>> {
+        match self {
+            ModuleName::Utf16(name) => {
+                let (stem, extension) = name.split_at_checked(name.len().checked_sub(4)?)?;
+                ModuleName::Utf16(extension)
+                    .eq_ignore_case(&ModuleName::Utf8(".dll"))
+                    .then_some(ModuleName::Utf16(stem))
+            }
+            ModuleName::Utf8(name) => {
+                let (stem, extension) = name.split_at_checked(name.len().checked_sub(4)?)?;
+                extension
+                    .eq_ignore_ascii_case(".dll")
+                    .then_some(ModuleName::Utf8(stem))
+            }
+        }
+    }
+
+    /// Whether this base name is `name`, ignoring case. `name` may omit the
+    /// `.dll` extension, so `kernel32` and `KERNEL32.DLL` both match
+    /// `kernel32.dll`, but `kernel` and `kernel32.dllx` do not.
+    pub fn has_same_base_dll_name(self: &Self, name: &str) -> bool {
+        let name = ModuleName::Utf8(name);
+        self.eq_ignore_case(&name)
+            || self
+                .strip_dll_extension()
+                .is_some_and(|stem| stem.eq_ignore_case(&name))
+    }
+
+    /// Whether this full name is the path `path`, ignoring case and treating
+    /// `/` and `\` alike.
+    pub fn has_same_full_dll_name(self: &Self, path: &str) -> bool {
+        let normalize = |character: char| match character {
+            '/' => '\\',
+            character => character,
+        };
+        self.chars()
+            .map(normalize)
+            .flat_map(char::to_lowercase)
+            .eq(path.chars().map(normalize).flat_map(char::to_lowercase))
+    }
+}
+
+impl Iterator for ModuleNameUnits<'_> {
+    type Item = u16;
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        match self {
+            ModuleNameUnits::Utf16(units) => units.next(),
+            ModuleNameUnits::Utf8(units) => units.next(),
+        }
+    }
+}
+
+impl Display for ModuleName<'_> {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleName::Utf16(_) => {
+                for character in self.chars() {
+                    f.write_char(character)?;
+                }
+                Ok(())
+            }
+            ModuleName::Utf8(name) => f.write_str(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec::Vec};
+    use core::ffi::c_void;
+
+    use super::{Module, ModuleName};
+    use crate::windows::test_image::TestImage;
+
+    const DLL_BASE: usize = 0x7ff0_0000_0000;
+
+    fn address(rva: u32) -> *const c_void {
+        (DLL_BASE + rva as usize) as *const c_void
+    }
+
+    #[test]
+    fn nearest_export_names_and_ordinals() {
+        let bytes = TestImage::new("sample.dll")
+            .export("Start", 0x2000)
+            .export_by_ordinal(0x2100)
+            .export("Stop", 0x2200)
+            .forward("Sleep", "kernel32.Sleep")
+            .build();
+        let module = Module::from_file("sample.dll", DLL_BASE as *const c_void, &bytes).unwrap();
+
+        let nearest = |rva| {
+            module
+                .nearest_export(address(rva))
+                .map(|nearest| nearest.to_string())
+        };
+        assert_eq!(nearest(0x2000).as_deref(), Some("sample!Start"));
+        assert_eq!(nearest(0x2010).as_deref(), Some("sample!Start+0x10"));
+        assert_eq!(nearest(0x2100).as_deref(), Some("sample!#2"));
+        assert_eq!(nearest(0x2180).as_deref(), Some("sample!#2+0x80"));
+        assert_eq!(nearest(0x2200).as_deref(), Some("sample!Stop"));
+        assert_eq!(nearest(0x1010).as_deref(), Some("sample+0x1010"));
+        assert_eq!(nearest(TestImage::SIZE_OF_IMAGE), None);
+        assert!(!module.contains_address((DLL_BASE - 1) as *const c_void));
+
+        let ordinal_only = module.nearest_export(address(0x2104)).unwrap();
+        assert_eq!(ordinal_only.export(), None);
+        assert_eq!(ordinal_only.ordinal(), Some(2));
+        assert_eq!(ordinal_only.offset(), &4);
+        assert_eq!(format!("{}", ordinal_only), "sample!#2+0x4");
+    }
+
+    #[test]
+    fn nearest_export_prefers_names_of_aliases() {
+        let bytes = TestImage::new("sample.dll")
+            .export_by_ordinal(0x2000)
+            .export("Alias", 0x2000)
+            .build();
+        let module = Module::from_file("sample.dll", DLL_BASE as *const c_void, &bytes).unwrap();
+        let nearest = module.nearest_export(address(0x2008)).unwrap();
+        assert_eq!(nearest.export(), Some("Alias"));
+        assert_eq!(nearest.to_string(), "sample!Alias+0x8");
+    }
+
+    fn utf16(name: &str) -> Vec<u16> {
+        name.encode_utf16().collect()
+    }
+
+    /// Runs `check` on `name` as stored by the loader and as supplied by
+    /// the user.
+    fn both(name: &str, check: impl Fn(ModuleName)) {
+        check(ModuleName::Utf16(&utf16(name)));
+        check(ModuleName::Utf8(name));
+    }
+
+    #[test]
+    fn base_names_match_ignoring_case_and_extension() {
+        both("kernel32.dll", |name| {
+            assert!(name.has_same_base_dll_name("kernel32.dll"));
+            assert!(name.has_same_base_dll_name("KERNEL32.DLL"));
+            assert!(name.has_same_base_dll_name("Kernel32"));
+            assert!(!name.has_same_base_dll_name("kernel"));
+            assert!(!name.has_same_base_dll_name("kernel32.dllx"));
+            assert!(!name.has_same_base_dll_name("kernel32.dll.mui"));
+            assert!(!name.has_same_base_dll_name("C:\\Windows\\System32\\kernel32.dll"));
+        });
+        both("KERNEL32.DLL", |name| {
+            assert!(name.has_same_base_dll_name("kernel32.dll"));
+            assert!(name.has_same_base_dll_name("kernel32"));
+        });
+        // Only `.dll` is optional; other extensions must be spelled out.
+        both("kernel32.dll.mui", |name| {
+            assert!(name.has_same_base_dll_name("KERNEL32.DLL.MUI"));
+            assert!(!name.has_same_base_dll_name("kernel32.dll"));
+            assert!(!name.has_same_base_dll_name("kernel32"));
+        });
+        both("ntoskrnl.exe", |name| {
+            assert!(name.has_same_base_dll_name("ntoskrnl.exe"));
+            assert!(!name.has_same_base_dll_name("ntoskrnl"));
+        });
+    }
+
+    #[test]
+    fn strips_only_a_dll_extension() {
+        let stripped = |name: &str| {
+            let utf16 = utf16(name);
+            let from_utf16 = ModuleName::Utf16(&utf16)
+                .strip_dll_extension()
+                .map(|stem| stem.to_string());
+            let from_utf8 = ModuleName::Utf8(name)
+                .strip_dll_extension()
+                .map(|stem| stem.to_string());
+            assert_eq!(from_utf16, from_utf8);
+            from_utf8
+        };
+        assert_eq!(stripped("kernel32.dll").as_deref(), Some("kernel32"));
+        assert_eq!(stripped("KERNEL32.DLL").as_deref(), Some("KERNEL32"));
+        assert_eq!(stripped(".dll").as_deref(), Some(""));
+        assert_eq!(stripped("kernel32.dll.mui").as_deref(), None);
+        assert_eq!(stripped("kernel32").as_deref(), None);
+        assert_eq!(stripped("dll").as_deref(), None);
+    }
+
+    #[test]
+    fn full_names_match_whole_paths() {
+        both("C:\\Windows\\System32\\KERNEL32.DLL", |name| {
+            assert!(name.has_same_full_dll_name("c:\\windows\\system32\\kernel32.dll"));
+            assert!(name.has_same_full_dll_name("C:/Windows/System32/kernel32.dll"));
+            assert!(!name.has_same_full_dll_name("kernel32.dll"));
+            assert!(!name.has_same_full_dll_name("C:\\Windows\\SysWOW64\\kernel32.dll"));
+            assert!(!name.has_same_full_dll_name("C:\\Windows\\System32\\kernel32"));
+        });
+        both("kernel32.dll", |name| {
+            assert!(!name.has_same_full_dll_name("C:\\Windows\\System32\\kernel32.dll"));
+        });
+    }
+}
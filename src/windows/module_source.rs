@@ -0,0 +1,256 @@
+use core::ffi::c_void;
+
+use super::{ApiSetMap, LdrDataTableEntry, Module, Peb};
+
+/// Where modules are looked up when resolving exports and forwarders.
+/// [`PebModules`] walks the loader list of the current process;
+/// [`ModuleList`] serves modules supplied by the caller, so resolution can
+/// run against images read from files on any platform.
+pub trait ModuleSource {
+    fn modules(self: &Self) -> impl Iterator<Item = Module<'_>>;
+
+    /// The API set schema used to map contracts to their hosts, if any.
+    fn api_set_map(self: &Self) -> Option<ApiSetMap<'_>> {
+        None
+    }
+
+    fn find(self: &Self, name: &str) -> Option<Module<'_>> {
+        self.modules()
+            .find(|module| module.base_dll_name().has_same_base_dll_name(name))
+    }
+
+    fn find_by_full_dll_name(self: &Self, path: &str) -> Option<Module<'_>> {
+        self.modules()
+            .find(|module| module.full_dll_name().has_same_full_dll_name(path))
+    }
+
+    /// The module whose image holds `address`.
+    fn containing(self: &Self, address: *const c_void) -> Option<Module<'_>> {
+        self.modules()
+            .find(|module| module.contains_address(address))
+    }
+
+    /// Like [`ModuleSource::find`], but first maps API set contracts such
+    /// as `api-ms-win-core-synch-l1-2-0.dll`, which are never in the loader
+    /// list, to the module hosting them for `importer`.
+    fn resolve_module(self: &Self, name: &str, importer: Option<&Module>) -> Option<Module<'_>> {
+        let host = self.api_set_map().and_then(|api_set_map| {
+            api_set_map.resolve(name, importer.map(|importer| importer.base_dll_name()))
+        });
+        match host {
+            Some(host) => self
+                .modules()
+                .find(|module| host.eq_units_ignore_ascii_case(module.base_dll_name().units())),
+            None => self.find(name),
+        }
+    }
+}
+
+/// The loader list and API set schema of the current process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PebModules;
+
+/// Modules supplied by the caller, e.g. read with
+/// [`Module::from_file`], and optionally an API set schema to resolve
+/// contracts with.
+#[derive(Debug, Clone, Copy)]
+pub struct ModuleList<'a> {
+    modules: &'a [Module<'a>],
+    api_set_map: Option<ApiSetMap<'a>>,
+}
+
+impl ModuleSource for PebModules {
+    fn modules(self: &Self) -> impl Iterator<Item = Module<'_>> {
+        LdrDataTableEntry::iter().map(|entry| entry.module())
+    }
+
+    fn api_set_map(self: &Self) -> Option<ApiSetMap<'_>> {
+        Peb::current().api_set_map()
+    }
+}
+
+impl<'a> ModuleList<'a> {
+    pub fn new(modules: &'a [Module<'a>]) -> Self {
+        Self {
+            modules,
+            api_set_map: None,
+        }
+    }
+
+    pub fn with_api_set_map(self: Self, api_set_map: ApiSetMap<'a>) -> Self {
+        Self {
+            api_set_map: Some(api_set_map),
+            ..self
+        }
+    }
+}
+
+impl ModuleSource for ModuleList<'_> {
+    fn modules(self: &Self) -> impl Iterator<Item = Module<'_>> {
+        self.modules.iter().cloned()
+    }
+
+    fn api_set_map(self: &Self) -> Option<ApiSetMap<'_>> {
+        self.api_set_map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, vec::Vec};
+    use core::ffi::c_void;
+
+    use super::{ModuleList, ModuleSource};
+    use crate::windows::{
+        test_image::{api_set_schema, TestImage},
+        ApiSetMap, ForwarderChain, Module, ResolveFailure,
+    };
+
+    fn dll_base(index: usize) -> *const c_void {
+        ((index + 1) * 0x100_0000) as *const c_void
+    }
+
+    fn modules<'a>(names: &[&'a str], images: &'a [Vec<u8>]) -> Vec<Module<'a>> {
+        names
+            .iter()
+            .zip(images)
+            .enumerate()
+            .map(|(index, (name, bytes))| Module::from_file(name, dll_base(index), bytes).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn resolves_direct_exports() {
+        let images = [TestImage::new("kernelbase.dll")
+            .export("Sleep", 0x2010)
+            .export_by_ordinal(0x2020)
+            .build()];
+        let modules = modules(&["kernelbase.dll"], &images);
+        let list = ModuleList::new(&modules);
+
+        let kernelbase = list.find("KERNELBASE").unwrap();
+        let sleep = kernelbase.resolve_export(&list, "Sleep").unwrap();
+        assert_eq!(sleep.address(), dll_base(0).wrapping_byte_add(0x2010));
+        assert!(!sleep.is_forwarded());
+        let by_ordinal = kernelbase.resolve_export_by_ordinal(&list, 2).unwrap();
+        assert_eq!(by_ordinal.address(), dll_base(0).wrapping_byte_add(0x2020));
+        let missing = kernelbase.resolve_export(&list, "Missing").unwrap_err();
+        assert_eq!(missing.failure(), &ResolveFailure::ExportNotFound);
+        assert_eq!(missing.last_hop(), None);
+        assert_eq!(format!("{}", missing), "export not found");
+    }
+
+    #[test]
+    fn follows_forwarder_chains() {
+        let images = [
+            TestImage::new("a.dll").forward("Run", "b.Run").build(),
+            TestImage::new("b.dll").forward("Run", "c.#1").build(),
+            TestImage::new("c.dll").export("Walk", 0x2040).build(),
+        ];
+        let modules = modules(&["a.dll", "b.dll", "c.dll"], &images);
+        let list = ModuleList::new(&modules);
+
+        let run = modules[0].resolve_export(&list, "Run").unwrap();
+        assert_eq!(run.address(), dll_base(2).wrapping_byte_add(0x2040));
+        assert_eq!(run.chain().hops(), ["b.Run", "c.#1"]);
+        assert_eq!(format!("{}", run.chain()), "b.Run -> c.#1");
+    }
+
+    #[test]
+    fn fails_on_missing_modules_and_cycles() {
+        let images = [
+            TestImage::new("a.dll")
+                .forward("Loop", "b.Loop")
+                .forward("Gone", "missing.Gone")
+                .forward("Bad", "nodot")
+                .build(),
+            TestImage::new("b.dll").forward("Loop", "a.Loop").build(),
+        ];
+        let modules = modules(&["a.dll", "b.dll"], &images);
+        let list = ModuleList::new(&modules);
+
+        let cycle = modules[0].resolve_export(&list, "Loop").unwrap_err();
+        assert_eq!(cycle.failure(), &ResolveFailure::Cycle);
+        assert_eq!(cycle.depth(), 2);
+        assert_eq!(cycle.last_hop(), Some("a.Loop"));
+        assert_eq!(
+            format!("{}", cycle),
+            "forwarder cycle at a.Loop after 2 forwarders"
+        );
+        let gone = modules[0].resolve_export(&list, "Gone").unwrap_err();
+        assert_eq!(gone.failure(), &ResolveFailure::ModuleNotLoaded);
+        let bad = modules[0].resolve_export(&list, "Bad").unwrap_err();
+        assert_eq!(bad.failure(), &ResolveFailure::MalformedForwarder);
+    }
+
+    #[test]
+    fn stops_after_max_depth_forwarders() {
+        // m0.F -> m1.F -> ... -> m17.F, 17 forwarders in all.
+        let count = ForwarderChain::MAX_DEPTH + 2;
+        let names: Vec<_> = (0..count).map(|index| format!("m{}.dll", index)).collect();
+        let forwarders: Vec<_> = (1..count).map(|index| format!("m{}.F", index)).collect();
+        let images: Vec<_> = (0..count)
+            .map(|index| match forwarders.get(index) {
+                Some(forwarder) => TestImage::new(&names[index])
+                    .forward("F", forwarder)
+                    .build(),
+                None => TestImage::new(&names[index]).export("F", 0x2000).build(),
+            })
+            .collect();
+        let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        let modules = modules(&names, &images);
+        let list = ModuleList::new(&modules);
+
+        let deepest = modules[1].resolve_export(&list, "F").unwrap();
+        assert_eq!(deepest.chain().len(), ForwarderChain::MAX_DEPTH);
+        assert_eq!(
+            deepest.address(),
+            dll_base(count - 1).wrapping_byte_add(0x2000)
+        );
+        let too_deep = modules[0].resolve_export(&list, "F").unwrap_err();
+        assert_eq!(too_deep.failure(), &ResolveFailure::TooDeep);
+        assert_eq!(too_deep.depth(), ForwarderChain::MAX_DEPTH);
+        let last_hop = format!("m{}.F", ForwarderChain::MAX_DEPTH);
+        assert_eq!(too_deep.last_hop(), Some(last_hop.as_str()));
+    }
+
+    #[test]
+    fn redirects_api_sets() {
+        let schema = api_set_schema(
+            6,
+            &[(
+                "api-ms-win-core-synch-l1-2-0",
+                &[("", "kernel32.dll"), ("kernel32.dll", "kernelbase.dll")],
+            )],
+        );
+        let images = [
+            TestImage::new("kernel32.dll")
+                .forward("Sleep", "api-ms-win-core-synch-l1-2-0.Sleep")
+                .build(),
+            TestImage::new("kernelbase.dll")
+                .export("Sleep", 0x2010)
+                .build(),
+            TestImage::new("user32.dll")
+                .forward("Sleep", "api-ms-win-core-synch-l1-2-1.Sleep")
+                .build(),
+        ];
+        let modules = modules(&["kernel32.dll", "kernelbase.dll", "user32.dll"], &images);
+        let without_schema = ModuleList::new(&modules);
+        let list = without_schema.with_api_set_map(ApiSetMap::parse(&schema).unwrap());
+
+        // kernel32 is redirected past itself to kernelbase.
+        let sleep = modules[0].resolve_export(&list, "Sleep").unwrap();
+        assert_eq!(sleep.address(), dll_base(1).wrapping_byte_add(0x2010));
+        // Other importers get kernel32, which forwards on to kernelbase.
+        let sleep = modules[2].resolve_export(&list, "Sleep").unwrap();
+        assert_eq!(sleep.address(), dll_base(1).wrapping_byte_add(0x2010));
+        assert_eq!(sleep.chain().len(), 2);
+
+        let unresolved = modules[0]
+            .resolve_export(&without_schema, "Sleep")
+            .unwrap_err();
+        assert_eq!(unresolved.failure(), &ResolveFailure::ModuleNotLoaded);
+        let host = list.resolve_module("api-ms-win-core-synch-l1-2-0.dll", None);
+        assert_eq!(host.unwrap().dll_base(), dll_base(0));
+    }
+}
@@ -0,0 +1,708 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::DemangledName;
+
+/// Undecorates Microsoft C++ names, printing them the way `llvm-undname`
+/// does.
+pub struct MsvcDemangler<'s> {
+    symbol: &'s [u8],
+    position: usize,
+    names: Vec<String>,
+    types: Vec<String>,
+    depth: usize,
+}
+
+enum UnqualifiedName {
+    Name(String),
+    Constructor,
+    Destructor,
+    Conversion,
+    Special(&'static str),
+}
+
+struct FunctionType {
+    return_type: Option<String>,
+    calling_convention: &'static str,
+    parameters: String,
+}
+
+impl<'s> MsvcDemangler<'s> {
+    const MAX_BACK_REFERENCES: usize = 10;
+    /// Nested types and templates recurse; bound them so hostile symbols
+    /// cannot exhaust the stack.
+    const MAX_DEPTH: usize = 256;
+
+    pub fn demangle(symbol: &'s str) -> Option<DemangledName> {
+        let mut demangler = Self {
+            symbol: symbol.as_bytes(),
+            position: 0,
+            names: Vec::new(),
+            types: Vec::new(),
+            depth: 0,
+        };
+        let demangled_name = demangler.symbol()?;
+        demangler
+            .position
+            .eq(&demangler.symbol.len())
+            .then_some(demangled_name)
+    }
+
+    fn peek(self: &Self) -> Option<u8> {
+        self.symbol.get(self.position).cloned()
+    }
+
+    fn next(self: &mut Self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn consume(self: &mut Self, prefix: &[u8]) -> bool {
+        let matches = self.symbol[self.position..].starts_with(prefix);
+        if matches {
+            self.position += prefix.len();
+        }
+        matches
+    }
+
+    fn expect(self: &mut Self, byte: u8) -> Option<()> {
+        self.next()?.eq(&byte).then_some(())
+    }
+
+    fn nested<T>(self: &mut Self, parse: fn(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth.ge(&MsvcDemangler::MAX_DEPTH) {
+            return None;
+        }
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        parsed
+    }
+
+    fn symbol(self: &mut Self) -> Option<DemangledName> {
+        self.expect(b'?')?;
+        let unqualified_name = self.unqualified_name(true)?;
+        let scopes = self.scopes()?;
+        let qualify = |name: &str| match scopes.is_empty() {
+            true => name.to_string(),
+            false => format!("{}::{}", scopes, name),
+        };
+        let innermost_scope = scopes.rsplit("::").next().unwrap_or("").to_string();
+
+        let kind = self.next()?;
+        match kind {
+            b'0'..=b'4' => {
+                let name = match &unqualified_name {
+                    UnqualifiedName::Name(name) => qualify(name),
+                    _ => return None,
+                };
+                let variable_type = self.type_()?;
+                // Pointers carry their own qualifiers.
+                let storage = match self.storage_class()? {
+                    _ if variable_type.ends_with(['*', '&']) => "",
+                    storage => storage,
+                };
+                let access = match kind {
+                    b'0' => "private: static ",
+                    b'1' => "protected: static ",
+                    b'2' => "public: static ",
+                    _ => "",
+                };
+                let separator = match variable_type.ends_with(['*', '&']) {
+                    true => "",
+                    false => " ",
+                };
+                let signature = format!(
+                    "{}{}{}{}{}",
+                    access, variable_type, storage, separator, name
+                );
+                Some(DemangledName::new(name, signature))
+            }
+            b'6' | b'7' => {
+                let special = match unqualified_name {
+                    UnqualifiedName::Special(special) => special,
+                    _ => return None,
+                };
+                let name = qualify(special);
+                let storage = self.storage_class()?;
+                let mut signature = format!("{} {}", storage.trim_start(), name);
+                while !self.consume(b"@") {
+                    signature.push_str(&format!("{{for `{}'}}", self.scopes()?));
+                }
+                Some(DemangledName::new(name, signature.trim_start().to_string()))
+            }
+            b'A'..=b'Z' => {
+                let (access, modifier, is_member, is_thunk) = match kind {
+                    b'Y' | b'Z' => ("", "", false, false),
+                    _ => {
+                        let access = match (kind - b'A') / 8 {
+                            0 => "private: ",
+                            1 => "protected: ",
+                            _ => "public: ",
+                        };
+                        match ((kind - b'A') % 8) / 2 {
+                            0 => (access, "", true, false),
+                            1 => (access, "static ", false, false),
+                            2 => (access, "virtual ", true, false),
+                            _ => (access, "virtual ", true, true),
+                        }
+                    }
+                };
+                let adjustor = match is_thunk {
+                    true => Some(self.number()?),
+                    false => None,
+                };
+                let this_qualifiers = match is_member {
+                    true => self.this_qualifiers()?,
+                    false => "",
+                };
+                let function = self.function_type()?;
+
+                let name = match &unqualified_name {
+                    UnqualifiedName::Name(name) => qualify(name),
+                    UnqualifiedName::Constructor => qualify(&innermost_scope),
+                    UnqualifiedName::Destructor => qualify(&format!("~{}", innermost_scope)),
+                    UnqualifiedName::Conversion => qualify(&format!(
+                        "operator {}",
+                        function.return_type.as_deref().unwrap_or("")
+                    )),
+                    UnqualifiedName::Special(special) => qualify(special),
+                };
+                let return_type = function.return_type;
+
+                let mut signature = String::new();
+                if is_thunk {
+                    signature.push_str("[thunk]:");
+                }
+                signature.push_str(access);
+                signature.push_str(modifier);
+                if let Some(return_type) = return_type {
+                    signature.push_str(&return_type);
+                    signature.push(' ');
+                }
+                signature.push_str(&format!(
+                    "{} {}({}){}",
+                    function.calling_convention, name, function.parameters, this_qualifiers
+                ));
+                if let Some(adjustor) = adjustor {
+                    signature.push_str(&format!("`adjustor{{{}}}'", adjustor));
+                }
+                Some(DemangledName::new(name, signature))
+            }
+            _ => None,
+        }
+    }
+
+    fn unqualified_name(self: &mut Self, allow_operator: bool) -> Option<UnqualifiedName> {
+        if self.consume(b"?$") {
+            let name = self.template_name()?;
+            self.remember_name(&name);
+            return Some(UnqualifiedName::Name(name));
+        }
+        if allow_operator && self.consume(b"?") {
+            return self.operator_name();
+        }
+        self.simple_name().map(UnqualifiedName::Name)
+    }
+
+    /// A name fragment terminated by `@`, or a back reference to one.
+    fn simple_name(self: &mut Self) -> Option<String> {
+        match self.peek()? {
+            digit @ b'0'..=b'9' => {
+                self.position += 1;
+                self.names.get((digit - b'0') as usize).cloned()
+            }
+            _ => {
+                let length = self.symbol[self.position..]
+                    .iter()
+                    .position(|byte| byte.eq(&b'@'))?;
+                let name =
+                    core::str::from_utf8(&self.symbol[self.position..self.position + length])
+                        .ok()?
+                        .to_string();
+                self.position += length + 1;
+                self.remember_name(&name);
+                Some(name)
+            }
+        }
+    }
+
+    fn remember_name(self: &mut Self, name: &str) {
+        if self.names.len().lt(&MsvcDemangler::MAX_BACK_REFERENCES)
+            && !self.names.iter().any(|known| known.eq(name))
+        {
+            self.names.push(name.to_string());
+        }
+    }
+
+    /// Template names carry their own back reference tables.
+    fn template_name(self: &mut Self) -> Option<String> {
+        let names = core::mem::take(&mut self.names);
+        let types = core::mem::take(&mut self.types);
+        let name = self.nested(Self::template_name_and_arguments);
+        self.names = names;
+        self.types = types;
+        name
+    }
+
+    fn template_name_and_arguments(self: &mut Self) -> Option<String> {
+        let name = match self.unqualified_name(true)? {
+            UnqualifiedName::Name(name) => name,
+            UnqualifiedName::Special(special) => special.to_string(),
+            _ => return None,
+        };
+        let mut arguments = Vec::new();
+        while !self.consume(b"@") {
+            if self.consume(b"$0") {
+                arguments.push(self.number()?.to_string());
+            } else if self.consume(b"$$V") || self.consume(b"$$Z") {
+                continue;
+            } else {
+                let start = self.position;
+                let argument = self.type_()?;
+                self.remember_type(start, &argument);
+                arguments.push(argument);
+            }
+        }
+        Some(format!("{}<{}>", name, arguments.join(", ")))
+    }
+
+    /// The enclosing scopes, innermost first in the symbol, printed
+    /// outermost first.
+    fn scopes(self: &mut Self) -> Option<String> {
+        let mut scopes = Vec::new();
+        while !self.consume(b"@") {
+            let scope = if self.consume(b"?$") {
+                let name = self.template_name()?;
+                self.remember_name(&name);
+                name
+            } else if self.consume(b"?A0x") {
+                let length = self.symbol[self.position..]
+                    .iter()
+                    .position(|byte| byte.eq(&b'@'))?;
+                self.position += length + 1;
+                let name = String::from("`anonymous namespace'");
+                self.remember_name(&name);
+                name
+            } else if self.peek()?.eq(&b'?') {
+                // Function-local scopes such as `?1??foo@@...` are not supported.
+                return None;
+            } else {
+                self.simple_name()?
+            };
+            scopes.push(scope);
+        }
+        scopes.reverse();
+        Some(scopes.join("::"))
+    }
+
+    fn operator_name(self: &mut Self) -> Option<UnqualifiedName> {
+        let name = match self.next()? {
+            b'0' => return Some(UnqualifiedName::Constructor),
+            b'1' => return Some(UnqualifiedName::Destructor),
+            b'B' => return Some(UnqualifiedName::Conversion),
+            b'2' => "operator new",
+            b'3' => "operator delete",
+            b'4' => "operator=",
+            b'5' => "operator>>",
+            b'6' => "operator<<",
+            b'7' => "operator!",
+            b'8' => "operator==",
+            b'9' => "operator!=",
+            b'A' => "operator[]",
+            b'C' => "operator->",
+            b'D' => "operator*",
+            b'E' => "operator++",
+            b'F' => "operator--",
+            b'G' => "operator-",
+            b'H' => "operator+",
+            b'I' => "operator&",
+            b'J' => "operator->*",
+            b'K' => "operator/",
+            b'L' => "operator%",
+            b'M' => "operator<",
+            b'N' => "operator<=",
+            b'O' => "operator>",
+            b'P' => "operator>=",
+            b'Q' => "operator,",
+            b'R' => "operator()",
+            b'S' => "operator~",
+            b'T' => "operator^",
+            b'U' => "operator|",
+            b'V' => "operator&&",
+            b'W' => "operator||",
+            b'X' => "operator*=",
+            b'Y' => "operator+=",
+            b'Z' => "operator-=",
+            b'_' => match self.next()? {
+                b'0' => "operator/=",
+                b'1' => "operator%=",
+                b'2' => "operator>>=",
+                b'3' => "operator<<=",
+                b'4' => "operator&=",
+                b'5' => "operator|=",
+                b'6' => "operator^=",
+                b'7' => return Some(UnqualifiedName::Special("`vftable'")),
+                b'8' => return Some(UnqualifiedName::Special("`vbtable'")),
+                b'9' => "`vcall'",
+                b'A' => "`typeof'",
+                b'B' => "`local static guard'",
+                b'D' => "`vbase destructor'",
+                b'E' => "`vector deleting destructor'",
+                b'F' => "`default constructor closure'",
+                b'G' => "`scalar deleting destructor'",
+                b'H' => "`vector constructor iterator'",
+                b'I' => "`vector destructor iterator'",
+                b'J' => "`vector vbase constructor iterator'",
+                b'L' => "`eh vector constructor iterator'",
+                b'M' => "`eh vector destructor iterator'",
+                b'U' => "operator new[]",
+                b'V' => "operator delete[]",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(UnqualifiedName::Special(name))
+    }
+
+    /// `?` introduces a negative number; `0`-`9` encode 1 to 10 and longer
+    /// values are hexadecimal with `A`-`P` as digits, terminated by `@`.
+    fn number(self: &mut Self) -> Option<i64> {
+        let negative = self.consume(b"?");
+        let value = match self.next()? {
+            digit @ b'0'..=b'9' => (digit - b'0') as i64 + 1,
+            b'@' => 0,
+            first @ b'A'..=b'P' => {
+                let mut value = (first - b'A') as i64;
+                loop {
+                    match self.next()? {
+                        b'@' => break value,
+                        digit @ b'A'..=b'P' => value = value * 16 + (digit - b'A') as i64,
+                        _ => return None,
+                    }
+                }
+            }
+            _ => return None,
+        };
+        Some(match negative {
+            true => -value,
+            false => value,
+        })
+    }
+
+    fn storage_class(self: &mut Self) -> Option<&'static str> {
+        while self.consume(b"E") || self.consume(b"I") || self.consume(b"F") {}
+        Some(match self.next()? {
+            b'A' => "",
+            b'B' => " const",
+            b'C' => " volatile",
+            b'D' => " const volatile",
+            _ => return None,
+        })
+    }
+
+    fn this_qualifiers(self: &mut Self) -> Option<&'static str> {
+        self.storage_class()
+    }
+
+    fn calling_convention(self: &mut Self) -> Option<&'static str> {
+        Some(match self.next()? {
+            b'A' | b'B' => "__cdecl",
+            b'C' | b'D' => "__pascal",
+            b'E' | b'F' => "__thiscall",
+            b'G' | b'H' => "__stdcall",
+            b'I' | b'J' => "__fastcall",
+            b'M' | b'N' => "__clrcall",
+            b'Q' => "__vectorcall",
+            _ => return None,
+        })
+    }
+
+    fn function_type(self: &mut Self) -> Option<FunctionType> {
+        let calling_convention = self.calling_convention()?;
+        let return_type = match self.consume(b"@") {
+            true => None,
+            false => Some(self.return_type()?),
+        };
+        let parameters = self.parameters()?;
+        // The throw specification is always empty in practice.
+        if !self.consume(b"Z") && !self.consume(b"_E") {
+            return None;
+        }
+        Some(FunctionType {
+            return_type,
+            calling_convention,
+            parameters,
+        })
+    }
+
+    fn return_type(self: &mut Self) -> Option<String> {
+        match self.consume(b"?") {
+            true => {
+                let qualifiers = self.storage_class()?;
+                Some(format!("{}{}", self.type_()?, qualifiers))
+            }
+            false => self.type_(),
+        }
+    }
+
+    fn parameters(self: &mut Self) -> Option<String> {
+        if self.consume(b"X") {
+            return Some(String::from("void"));
+        }
+        let mut parameters = Vec::new();
+        loop {
+            if self.consume(b"@") {
+                break;
+            }
+            if self.consume(b"Z") {
+                parameters.push(String::from("..."));
+                break;
+            }
+            let start = self.position;
+            let parameter = self.type_()?;
+            self.remember_type(start, &parameter);
+            parameters.push(parameter);
+        }
+        Some(parameters.join(", "))
+    }
+
+    /// Types whose encoding is longer than a single character can be
+    /// referred back to by index.
+    fn remember_type(self: &mut Self, start: usize, name: &str) {
+        if self.position - start > 1 && self.types.len().lt(&MsvcDemangler::MAX_BACK_REFERENCES) {
+            self.types.push(name.to_string());
+        }
+    }
+
+    fn type_(self: &mut Self) -> Option<String> {
+        self.nested(Self::nested_type)
+    }
+
+    fn nested_type(self: &mut Self) -> Option<String> {
+        let primitive = match self.next()? {
+            b'C' => "signed char",
+            b'D' => "char",
+            b'E' => "unsigned char",
+            b'F' => "short",
+            b'G' => "unsigned short",
+            b'H' => "int",
+            b'I' => "unsigned int",
+            b'J' => "long",
+            b'K' => "unsigned long",
+            b'M' => "float",
+            b'N' => "double",
+            b'O' => "long double",
+            b'X' => "void",
+            b'_' => match self.next()? {
+                b'D' => "__int8",
+                b'E' => "unsigned __int8",
+                b'F' => "__int16",
+                b'G' => "unsigned __int16",
+                b'H' => "__int32",
+                b'I' => "unsigned __int32",
+                b'J' => "__int64",
+                b'K' => "unsigned __int64",
+                b'L' => "__int128",
+                b'M' => "unsigned __int128",
+                b'N' => "bool",
+                b'Q' => "char8_t",
+                b'S' => "char16_t",
+                b'U' => "char32_t",
+                b'W' => "wchar_t",
+                _ => return None,
+            },
+            digit @ b'0'..=b'9' => return self.types.get((digit - b'0') as usize).cloned(),
+            kind @ (b'T' | b'U' | b'V') => {
+                let keyword = match kind {
+                    b'T' => "union",
+                    b'U' => "struct",
+                    _ => "class",
+                };
+                return Some(format!("{} {}", keyword, self.type_name()?));
+            }
+            b'W' => {
+                self.next()?;
+                return Some(format!("enum {}", self.type_name()?));
+            }
+            kind @ (b'P' | b'Q' | b'R' | b'S') => {
+                let pointer_qualifiers = match kind {
+                    b'P' => "",
+                    b'Q' => "const",
+                    b'R' => "volatile",
+                    _ => "const volatile",
+                };
+                return self.pointer("*", pointer_qualifiers);
+            }
+            b'A' => return self.pointer("&", ""),
+            b'B' => return self.pointer("&", "volatile"),
+            b'$' => {
+                if self.consume(b"$Q") {
+                    return self.pointer("&&", "");
+                }
+                if self.consume(b"$T") {
+                    return Some(String::from("std::nullptr_t"));
+                }
+                if self.consume(b"$C") {
+                    let qualifiers = self.storage_class()?;
+                    return Some(format!("{}{}", self.type_()?, qualifiers));
+                }
+                return None;
+            }
+            b'?' => {
+                let qualifiers = self.storage_class()?;
+                return Some(format!("{}{}", self.type_()?, qualifiers));
+            }
+            b'Y' => {
+                let extents = self.array_extents()?;
+                return Some(format!("{} {}", self.type_()?, extents));
+            }
+            _ => return None,
+        };
+        Some(primitive.to_string())
+    }
+
+    fn array_extents(self: &mut Self) -> Option<String> {
+        let dimensions = self.number()?;
+        let mut extents = String::new();
+        for _ in 0..dimensions {
+            extents.push_str(&format!("[{}]", self.number()?));
+        }
+        Some(extents)
+    }
+
+    fn type_name(self: &mut Self) -> Option<String> {
+        let name = match self.unqualified_name(false)? {
+            UnqualifiedName::Name(name) => name,
+            _ => return None,
+        };
+        let scopes = self.scopes()?;
+        Some(match scopes.is_empty() {
+            true => name,
+            false => format!("{}::{}", scopes, name),
+        })
+    }
+
+    /// The pointee of a pointer or reference, or the signature of a function
+    /// pointer.
+    fn pointer(self: &mut Self, declarator: &str, pointer_qualifiers: &str) -> Option<String> {
+        if self.consume(b"6") {
+            let function = self.function_type()?;
+            return Some(format!(
+                "{} ({} {}{})({})",
+                function.return_type.as_deref().unwrap_or("void"),
+                function.calling_convention,
+                declarator,
+                pointer_qualifiers,
+                function.parameters
+            ));
+        }
+        let pointee_qualifiers = self.storage_class()?;
+        if self.consume(b"Y") {
+            let extents = self.array_extents()?;
+            return Some(format!(
+                "{}{} ({}{}){}",
+                self.type_()?,
+                pointee_qualifiers,
+                declarator,
+                pointer_qualifiers,
+                extents
+            ));
+        }
+        let pointee = format!("{}{}", self.type_()?, pointee_qualifiers);
+        let separator = match pointee.ends_with(['*', '&']) {
+            true => "",
+            false => " ",
+        };
+        Some(format!(
+            "{}{}{}{}",
+            pointee, separator, declarator, pointer_qualifiers
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString};
+
+    use super::MsvcDemangler;
+
+    fn signature(symbol: &str) -> Option<alloc::string::String> {
+        MsvcDemangler::demangle(symbol).map(|name| name.signature().to_string())
+    }
+
+    #[test]
+    fn demangles_functions() {
+        assert_eq!(
+            signature("?Foo@Bar@@QEAAXH@Z").as_deref(),
+            Some("public: void __cdecl Bar::Foo(int)")
+        );
+        assert_eq!(
+            signature("?f@@YAXPEAH@Z").as_deref(),
+            Some("void __cdecl f(int *)")
+        );
+        assert_eq!(
+            signature("?call@@YAHP6AHH@Z@Z").as_deref(),
+            Some("int __cdecl call(int (__cdecl *)(int))")
+        );
+        assert_eq!(
+            signature("??Hui@@YA?AUPoint@0@AEBU10@0@Z").as_deref(),
+            Some("struct ui::Point __cdecl ui::operator+(struct ui::Point const &, struct ui::Point const &)")
+        );
+    }
+
+    #[test]
+    fn demangles_structors_and_templates() {
+        let constructor = MsvcDemangler::demangle("??0Widget@ui@@QEAA@XZ").unwrap();
+        assert_eq!(constructor.name(), "ui::Widget::Widget");
+        assert_eq!(
+            constructor.signature(),
+            "public: __cdecl ui::Widget::Widget(void)"
+        );
+        assert_eq!(
+            signature("??1Widget@ui@@UEAA@XZ").as_deref(),
+            Some("public: virtual __cdecl ui::Widget::~Widget(void)")
+        );
+        assert_eq!(
+            signature("?get@?$vector@H@std@@QEBAAEBH_K@Z").as_deref(),
+            Some("public: int const & __cdecl std::vector<int>::get(unsigned __int64) const")
+        );
+    }
+
+    #[test]
+    fn demangles_variables() {
+        assert_eq!(
+            signature("??_7Widget@ui@@6B@").as_deref(),
+            Some("const ui::Widget::`vftable'")
+        );
+        assert_eq!(
+            signature("?count@Widget@ui@@2HA").as_deref(),
+            Some("public: static int ui::Widget::count")
+        );
+        assert_eq!(
+            signature("?name@@3PEBDEB").as_deref(),
+            Some("char const *name")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_symbols() {
+        assert_eq!(signature("Foo"), None);
+        assert_eq!(signature("?Foo@Bar@@QEAAXH"), None);
+        assert_eq!(signature("?Foo@Bar@@QEAAXH@Ztrailing"), None);
+    }
+
+    #[test]
+    fn rejects_deeply_nested_symbols() {
+        let nested = format!("?f@@YAX{}H@Z", "PEA".repeat(100));
+        assert!(signature(&nested).is_some());
+        let pointers = format!("?f@@YAX{}H@Z", "PEA".repeat(5000));
+        assert_eq!(signature(&pointers), None);
+        let templates = format!("?f@{}@YAXXZ", "?$".repeat(5000));
+        assert_eq!(signature(&templates), None);
+    }
+}
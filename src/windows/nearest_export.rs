@@ -0,0 +1,66 @@
+use core::fmt::{self, Display, Formatter};
+
+use super::ModuleName;
+
+/// An address described relative to the closest export at or before it,
+/// or to the module base when no export precedes it.
+#[derive(Debug, Clone, Copy)]
+pub struct NearestExport<'a> {
+    module: ModuleName<'a>,
+    export: Option<&'a str>,
+    ordinal: Option<u32>,
+    offset: u32,
+}
+
+impl<'a> NearestExport<'a> {
+    pub fn new(
+        module: ModuleName<'a>,
+        export: Option<&'a str>,
+        ordinal: Option<u32>,
+        offset: u32,
+    ) -> Self {
+        Self {
+            module,
+            export,
+            ordinal,
+            offset,
+        }
+    }
+
+    pub fn module(self: &Self) -> &ModuleName<'a> {
+        &self.module
+    }
+
+    /// The export's name; `None` without an export or for one exported by
+    /// ordinal only.
+    pub fn export(self: &Self) -> Option<&'a str> {
+        self.export
+    }
+
+    /// The biased ordinal of the export, named or not.
+    pub fn ordinal(self: &Self) -> Option<u32> {
+        self.ordinal
+    }
+
+    /// Bytes past the export, or past the module base without one.
+    pub fn offset(self: &Self) -> &u32 {
+        &self.offset
+    }
+}
+
+/// `kernel32!Sleep+0x1c`, `kernel32!Sleep`, `kernel32!#12+0x1c` or
+/// `kernel32+0x1c`.
+impl Display for NearestExport<'_> {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.module.stem())?;
+        match (self.export, self.ordinal) {
+            (Some(export), _) => write!(f, "!{}", export)?,
+            (None, Some(ordinal)) => write!(f, "!#{}", ordinal)?,
+            (None, None) => {}
+        }
+        if self.offset.ne(&0) || self.ordinal.is_none() {
+            write!(f, "+{:#x}", self.offset)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use super::memchr::memchr;
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    bytes: [u8; Pattern::MAX_LEN],
+    mask: [u8; Pattern::MAX_LEN],
+    len: usize,
+    anchor: Option<usize>,
+}
+
+pub struct PatternMatches<'p, 'h> {
+    pattern: &'p Pattern,
+    haystack: &'h [u8],
+    position: usize,
+}
+
+impl Pattern {
+    pub const MAX_LEN: usize = 256;
+
+    /// Bytes that show up most in x86 code and data, most frequent first.
+    /// Anything missing from this list is treated as rarer than all of them.
+    const COMMON_BYTES: [u8; 64] = [
+        0x00, 0xff, 0x48, 0x8b, 0x89, 0x24, 0x0f, 0x4c, 0x01, 0x85, 0xc0, 0x44, 0x83, 0xe8, 0x8d,
+        0xcc, 0x20, 0x08, 0x74, 0x10, 0x41, 0x45, 0x75, 0x40, 0x49, 0x30, 0x28, 0x4d, 0x02, 0x04,
+        0x03, 0x38, 0x18, 0x50, 0x33, 0xd2, 0xc3, 0x90, 0x84, 0x05, 0x80, 0xc9, 0x0d, 0x5c, 0x54,
+        0x58, 0x60, 0x68, 0x70, 0x78, 0xeb, 0x06, 0x07, 0x0c, 0x14, 0x1c, 0x3b, 0x39, 0x66, 0xc7,
+        0x8a, 0x88, 0xb8, 0xe9,
+    ];
+
+    /// Compiles an IDA-style signature such as `48 8B 05 ?? ?? ?? ?? 48 85 C0`.
+    /// Either nibble of a byte may be a `?` wildcard, and a lone `?` stands
+    /// for a whole byte.
+    pub fn parse(signature: &str) -> Option<Self> {
+        let mut pattern = Self {
+            bytes: [0; Pattern::MAX_LEN],
+            mask: [0; Pattern::MAX_LEN],
+            len: 0,
+            anchor: None,
+        };
+
+        for token in signature.split_ascii_whitespace() {
+            let (byte, mask) = match token.as_bytes() {
+                [b'?'] => (0, 0),
+                [high, low] => {
+                    let (high, high_mask) = Pattern::parse_nibble(high.clone())?;
+                    let (low, low_mask) = Pattern::parse_nibble(low.clone())?;
+                    ((high << 4) | low, (high_mask << 4) | low_mask)
+                }
+                _ => return None,
+            };
+            if pattern.len.eq(&Pattern::MAX_LEN) {
+                return None;
+            }
+            pattern.bytes[pattern.len] = byte & mask;
+            pattern.mask[pattern.len] = mask;
+            pattern.len += 1;
+        }
+
+        pattern.anchor = (0..pattern.len)
+            .filter(|index| pattern.mask[index.clone()].eq(&0xff))
+            .min_by_key(|index| Pattern::commonness(pattern.bytes[index.clone()]));
+        pattern.len.ne(&0).then_some(pattern)
+    }
+
+    fn commonness(byte: u8) -> usize {
+        let rank = Pattern::COMMON_BYTES
+            .iter()
+            .position(|common| common.eq(&byte));
+        match rank {
+            Some(rank) => Pattern::COMMON_BYTES.len() - rank,
+            None => 0,
+        }
+    }
+
+    fn parse_nibble(character: u8) -> Option<(u8, u8)> {
+        match character {
+            b'?' => Some((0, 0)),
+            _ => Some(((character as char).to_digit(16)? as u8, 0xf)),
+        }
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.len.eq(&0)
+    }
+
+    pub fn bytes(self: &Self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Per-byte masks; `0x00` is a full wildcard and `0xff` an exact byte.
+    pub fn mask(self: &Self) -> &[u8] {
+        &self.mask[..self.len]
+    }
+
+    /// Index of the rarest fully specified byte, which the scanner searches
+    /// for before checking the rest of the pattern.
+    pub fn anchor(self: &Self) -> Option<usize> {
+        self.anchor
+    }
+
+    /// Whether `bytes` starts with this pattern.
+    pub fn matches(self: &Self, bytes: &[u8]) -> bool {
+        bytes.len().ge(&self.len)
+            && bytes
+                .iter()
+                .zip(self.bytes())
+                .zip(self.mask())
+                .all(|((byte, expected), mask)| (byte & mask).eq(expected))
+    }
+
+    pub fn find(self: &Self, haystack: &[u8]) -> Option<usize> {
+        self.find_iter(haystack).next()
+    }
+
+    pub fn find_iter<'p, 'h>(self: &'p Self, haystack: &'h [u8]) -> PatternMatches<'p, 'h> {
+        PatternMatches {
+            pattern: self,
+            haystack,
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for PatternMatches<'_, '_> {
+    type Item = usize;
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        let last = self.haystack.len().checked_sub(self.pattern.len)?;
+        while self.position.le(&last) {
+            let position = match self.pattern.anchor {
+                Some(anchor) => {
+                    let needle = self.pattern.bytes[anchor];
+                    let candidates = &self.haystack[self.position + anchor..=last + anchor];
+                    match memchr(needle, candidates) {
+                        Some(candidate) => self.position + candidate,
+                        None => {
+                            self.position = last + 1;
+                            return None;
+                        }
+                    }
+                }
+                None => self.position,
+            };
+            self.position = position + 1;
+            if self.pattern.matches(&self.haystack[position..]) {
+                return Some(position);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::Pattern;
+
+    /// `mov [rsp+8], rbx; push rdi; sub rsp, 0x20; mov rax, [rip+..];
+    /// test rax, rax; je ..; call ..; int3; int3; mov rax, [rip+..];
+    /// test rax, rax`.
+    const CODE: [u8; 40] = [
+        0x48, 0x89, 0x5c, 0x24, 0x08, 0x57, 0x48, 0x83, 0xec, 0x20, 0x48, 0x8b, 0x05, 0x11, 0x22,
+        0x33, 0x44, 0x48, 0x85, 0xc0, 0x74, 0x05, 0xe8, 0x00, 0x00, 0x00, 0x00, 0xcc, 0xcc, 0x48,
+        0x8b, 0x05, 0xaa, 0xbb, 0xcc, 0xdd, 0x48, 0x85, 0xc0, 0xc3,
+    ];
+
+    #[test]
+    fn parses_signatures() {
+        let pattern = Pattern::parse("48 8B 05 ?? ?? ? ?? 48 85 c0").unwrap();
+        assert_eq!(pattern.len(), 10);
+        assert_eq!(
+            pattern.bytes(),
+            [0x48, 0x8b, 0x05, 0, 0, 0, 0, 0x48, 0x85, 0xc0]
+        );
+        assert_eq!(
+            pattern.mask(),
+            [0xff, 0xff, 0xff, 0, 0, 0, 0, 0xff, 0xff, 0xff]
+        );
+
+        let nibbles = Pattern::parse("4? ?8").unwrap();
+        assert_eq!(nibbles.bytes(), [0x40, 0x08]);
+        assert_eq!(nibbles.mask(), [0xf0, 0x0f]);
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        assert!(Pattern::parse("").is_none());
+        assert!(Pattern::parse("   ").is_none());
+        assert!(Pattern::parse("4").is_none());
+        assert!(Pattern::parse("488B").is_none());
+        assert!(Pattern::parse("48 GG").is_none());
+        assert!(Pattern::parse("??? 48").is_none());
+
+        let longest: Vec<&str> = (0..Pattern::MAX_LEN).map(|_| "90").collect();
+        assert!(Pattern::parse(&longest.join(" ")).is_some());
+        let too_long: Vec<&str> = (0..=Pattern::MAX_LEN).map(|_| "90").collect();
+        assert!(Pattern::parse(&too_long.join(" ")).is_none());
+    }
+
+    #[test]
+    fn anchors_on_the_rarest_exact_byte() {
+        // 0x05 is rarer than 0x48 and 0x8b; wildcards are never anchors.
+        assert_eq!(Pattern::parse("48 8B 05 ??").unwrap().anchor(), Some(2));
+        // 0xe8 is in the common list, 0x57 is not.
+        assert_eq!(Pattern::parse("E8 ?? 57").unwrap().anchor(), Some(2));
+        assert_eq!(Pattern::parse("4? ?? ?8").unwrap().anchor(), None);
+    }
+
+    #[test]
+    fn finds_every_match() {
+        let pattern = Pattern::parse("48 8B 05 ?? ?? ?? ?? 48 85 C0").unwrap();
+        assert_eq!(pattern.find(&CODE), Some(10));
+        assert_eq!(pattern.find_iter(&CODE).collect::<Vec<_>>(), [10, 29]);
+
+        let cc = Pattern::parse("CC").unwrap();
+        assert_eq!(cc.find_iter(&CODE).collect::<Vec<_>>(), [27, 28, 34]);
+
+        // Matches may overlap.
+        let zeros = Pattern::parse("00 00").unwrap();
+        assert_eq!(zeros.find_iter(&CODE).collect::<Vec<_>>(), [23, 24, 25]);
+    }
+
+    #[test]
+    fn matches_wildcards_and_haystack_ends() {
+        let unanchored = Pattern::parse("4? ?? 5?").unwrap();
+        assert_eq!(unanchored.find_iter(&CODE).collect::<Vec<_>>(), [0]);
+
+        let ret = Pattern::parse("85 C0 C3").unwrap();
+        assert_eq!(ret.find(&CODE), Some(37));
+        assert_eq!(ret.find(&CODE[..39]), None);
+        assert_eq!(ret.find(&[]), None);
+        assert!(ret.matches(&CODE[37..]));
+        assert!(!ret.matches(&CODE[38..]));
+    }
+}
@@ -0,0 +1,158 @@
+use alloc::vec::Vec;
+
+use super::Pattern;
+
+/// Matches many patterns in a single pass over the haystack. Each pattern is
+/// bucketed by its anchor byte, so every haystack byte only costs a lookup
+/// unless it is the anchor of some pattern.
+pub struct PatternSet<'p> {
+    patterns: &'p [Pattern],
+    buckets: Vec<Vec<usize>>,
+    unanchored: Vec<usize>,
+}
+
+pub struct PatternSetMatches<'s, 'p, 'h> {
+    set: &'s PatternSet<'p>,
+    haystack: &'h [u8],
+    position: usize,
+    pending: Vec<(usize, usize)>,
+}
+
+impl<'p> PatternSet<'p> {
+    pub fn new(patterns: &'p [Pattern]) -> Self {
+        let mut buckets = Vec::with_capacity(256);
+        buckets.resize_with(256, Vec::new);
+        let mut unanchored = Vec::new();
+        for (index, pattern) in patterns.iter().enumerate() {
+            match pattern.anchor() {
+                Some(anchor) => buckets[pattern.bytes()[anchor] as usize].push(index),
+                None => unanchored.push(index),
+            }
+        }
+        Self {
+            patterns,
+            buckets,
+            unanchored,
+        }
+    }
+
+    pub fn patterns(self: &Self) -> &'p [Pattern] {
+        self.patterns
+    }
+
+    /// Every `(pattern index, offset)` match, ordered by the position that
+    /// triggered the check rather than strictly by offset.
+    pub fn find_iter<'s, 'h>(self: &'s Self, haystack: &'h [u8]) -> PatternSetMatches<'s, 'p, 'h> {
+        PatternSetMatches {
+            set: self,
+            haystack,
+            position: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// The lowest offset of each pattern, indexed like [`PatternSet::patterns`].
+    pub fn find_first(self: &Self, haystack: &[u8]) -> Vec<Option<usize>> {
+        let mut first = Vec::new();
+        first.resize(self.patterns.len(), None);
+        for (index, offset) in self.find_iter(haystack) {
+            let slot: &mut Option<usize> = &mut first[index];
+            if slot.is_none_or(|found| offset.lt(&found)) {
+                *slot = Some(offset);
+            }
+        }
+        first
+    }
+}
+
+impl Iterator for PatternSetMatches<'_, '_, '_> {
+    type Item = (usize, usize);
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        loop {
+            if let Some(found) = self.pending.pop() {
+                return Some(found);
+            }
+            let position = self.position;
+            let byte = self.haystack.get(position)?;
+            self.position += 1;
+
+            for index in &self.set.unanchored {
+                if self.set.patterns[index.clone()].matches(&self.haystack[position..]) {
+                    self.pending.push((index.clone(), position));
+                }
+            }
+            for index in &self.set.buckets[byte.clone() as usize] {
+                let pattern = &self.set.patterns[index.clone()];
+                let start = match position.checked_sub(pattern.anchor().unwrap_or(0)) {
+                    Some(start) => start,
+                    None => continue,
+                };
+                if pattern.matches(&self.haystack[start..]) {
+                    self.pending.push((index.clone(), start));
+                }
+            }
+            self.pending.reverse();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::{Pattern, PatternSet};
+
+    const CODE: [u8; 24] = [
+        0x48, 0x89, 0x5c, 0x24, 0x08, 0x57, 0x48, 0x83, 0xec, 0x20, 0x48, 0x8b, 0x05, 0x11, 0x22,
+        0x33, 0x44, 0xcc, 0xcc, 0x48, 0x8b, 0x05, 0xaa, 0xc3,
+    ];
+
+    fn patterns() -> Vec<Pattern> {
+        [
+            "48 8B 05 ?? ??",
+            "CC",
+            "48 ?? ?? 24",
+            // No exact byte, so it is checked at every position.
+            "4? ??",
+            "90 90",
+            // Shares its anchor bucket with the first pattern.
+            "8B 05 AA",
+        ]
+        .iter()
+        .map(|signature| Pattern::parse(signature).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn finds_what_each_pattern_finds() {
+        let patterns = patterns();
+        let set = PatternSet::new(&patterns);
+
+        let mut expected: Vec<(usize, usize)> = patterns
+            .iter()
+            .enumerate()
+            .flat_map(|(index, pattern)| {
+                pattern.find_iter(&CODE).map(move |offset| (index, offset))
+            })
+            .collect();
+        let mut found: Vec<(usize, usize)> = set.find_iter(&CODE).collect();
+        expected.sort();
+        found.sort();
+        assert_eq!(found, expected);
+
+        assert_eq!(
+            set.find_first(&CODE),
+            vec![Some(10), Some(17), Some(0), Some(0), None, Some(20)]
+        );
+    }
+
+    #[test]
+    fn skips_matches_that_would_start_before_the_haystack() {
+        let patterns = [Pattern::parse("?? ?? 05 11").unwrap()];
+        let set = PatternSet::new(&patterns);
+        assert_eq!(set.find_iter(&CODE[11..]).count(), 0);
+        assert_eq!(set.find_iter(&CODE[10..]).collect::<Vec<_>>(), [(0, 0)]);
+        assert_eq!(PatternSet::new(&[]).find_first(&CODE), []);
+    }
+}
@@ -1,6 +1,6 @@
 use core::{arch::asm, ffi::c_void};
 
-use super::PebLdrData;
+use super::{ApiSetMap, PebLdrData};
 
 #[repr(C)]
 #[derive(Debug)]
@@ -8,7 +8,8 @@ pub struct Peb {
     reserved1: [u8; 2],
     being_debugged: u8,
     reserved2: [u8; 1],
-    reserved3: [*const c_void; 2],
+    reserved3: [*const c_void; 1],
+    image_base_address: *const c_void,
     ldr: *const PebLdrData,
     process_parameters: *const c_void,
     reserved4: [*const c_void; 3],
@@ -18,8 +19,19 @@ pub struct Peb {
     reserved7: *const c_void,
     reserved8: u32,
     atl_thunk_slist_ptr32: u32,
-    reserved9: [*const c_void; 45],
-    reserved10: [u8; 96],
+    api_set_map: *const c_void,
+    #[cfg(target_pointer_width = "64")]
+    reserved9: [u8; 0xa8],
+    #[cfg(target_pointer_width = "32")]
+    reserved9: [u8; 0x68],
+    os_major_version: u32,
+    os_minor_version: u32,
+    os_build_number: u16,
+    os_csd_version: u16,
+    #[cfg(target_pointer_width = "64")]
+    reserved10: [u8; 0x10c],
+    #[cfg(target_pointer_width = "32")]
+    reserved10: [u8; 0x9c],
     post_process_init_routine: *const c_void,
     reserved11: [u8; 128],
     reserved12: [*const c_void; 1],
@@ -27,16 +39,55 @@ pub struct Peb {
 }
 
 impl Peb {
+    #[cfg(target_arch = "x86_64")]
     pub fn current() -> &'static Peb {
         let nt_current_peb = || -> *const Peb { __readgsqword(0x60) as *const Peb };
         unsafe { &*nt_current_peb() }
     }
 
+    #[cfg(target_arch = "x86")]
+    pub fn current() -> &'static Peb {
+        let nt_current_peb = || -> *const Peb { __readfsdword(0x30) as *const Peb };
+        unsafe { &*nt_current_peb() }
+    }
+
+    /// The TEB is in the x18 platform register and points to the PEB at
+    /// offset 0x60, as on x86_64.
+    #[cfg(target_arch = "aarch64")]
+    pub fn current() -> &'static Peb {
+        let nt_current_peb = || -> *const Peb {
+            let teb = __readx18() as *const *const Peb;
+            unsafe { teb.byte_add(0x60).read() }
+        };
+        unsafe { &*nt_current_peb() }
+    }
+
+    pub fn image_base_address(self: &Self) -> *const c_void {
+        self.image_base_address
+    }
+
+    pub fn os_major_version(self: &Self) -> &u32 {
+        &self.os_major_version
+    }
+
+    pub fn os_minor_version(self: &Self) -> &u32 {
+        &self.os_minor_version
+    }
+
+    pub fn os_build_number(self: &Self) -> &u16 {
+        &self.os_build_number
+    }
+
     pub fn ldr(self: &Self) -> &PebLdrData {
         unsafe { &*self.ldr }
     }
+
+    pub fn api_set_map(self: &Self) -> Option<ApiSetMap<'static>> {
+        unsafe { ApiSetMap::from_ptr(self.api_set_map) }
+    }
 }
 
+#[cfg(target_arch = "x86_64")]
 fn __readgsqword(offset: u32) -> u64 {
     let out: u64;
     unsafe {
@@ -49,3 +100,30 @@ fn __readgsqword(offset: u32) -> u64 {
     }
     out
 }
+
+#[cfg(target_arch = "x86")]
+fn __readfsdword(offset: u32) -> u32 {
+    let out: u32;
+    unsafe {
+        asm!(
+            "mov {:e}, fs:[{:e}]",
+            lateout(reg) out,
+            in(reg) offset,
+            options(nostack, pure, readonly),
+        )
+    }
+    out
+}
+
+#[cfg(target_arch = "aarch64")]
+fn __readx18() -> u64 {
+    let out: u64;
+    unsafe {
+        asm!(
+            "mov {}, x18",
+            lateout(reg) out,
+            options(nomem, nostack, pure),
+        )
+    }
+    out
+}
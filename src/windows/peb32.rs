@@ -0,0 +1,68 @@
+use super::{memory_reader::read_value, MemoryReader, PebLdrData32};
+
+/// The `PEB` of a 32-bit process. A 64-bit process finds the `PEB32` of a
+/// WOW64 target with `NtQueryInformationProcess(ProcessWow64Information)`
+/// and reads it through a [`MemoryReader`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Peb32 {
+    reserved1: [u8; 2],
+    being_debugged: u8,
+    reserved2: [u8; 1],
+    reserved3: [u32; 2],
+    ldr: u32,
+    process_parameters: u32,
+    reserved4: [u32; 3],
+    atl_thunk_slist_ptr: u32,
+    reserved5: u32,
+    reserved6: u32,
+    reserved7: u32,
+    reserved8: u32,
+    atl_thunk_slist_ptr32: u32,
+    api_set_map: u32,
+    reserved9: [u8; 0x68],
+    os_major_version: u32,
+    os_minor_version: u32,
+    os_build_number: u16,
+    os_csd_version: u16,
+    reserved10: [u8; 0x9c],
+    post_process_init_routine: u32,
+    reserved11: [u8; 128],
+    reserved12: [u32; 1],
+    session_id: u32,
+}
+
+impl Peb32 {
+    pub fn read(reader: &(impl MemoryReader + ?Sized), address: u64) -> Option<Self> {
+        read_value(reader, address)
+    }
+
+    pub fn being_debugged(self: &Self) -> bool {
+        self.being_debugged.ne(&0)
+    }
+
+    pub fn os_major_version(self: &Self) -> &u32 {
+        &self.os_major_version
+    }
+
+    pub fn os_minor_version(self: &Self) -> &u32 {
+        &self.os_minor_version
+    }
+
+    pub fn os_build_number(self: &Self) -> &u16 {
+        &self.os_build_number
+    }
+
+    /// The address of the loader data in the target.
+    pub fn ldr(self: &Self) -> &u32 {
+        &self.ldr
+    }
+
+    pub fn api_set_map(self: &Self) -> &u32 {
+        &self.api_set_map
+    }
+
+    pub fn read_ldr(self: &Self, reader: &(impl MemoryReader + ?Sized)) -> Option<PebLdrData32> {
+        PebLdrData32::read(reader, self.ldr)
+    }
+}
@@ -1,6 +1,8 @@
 use core::ffi::c_void;
 
-use super::ListEntry;
+use super::{
+    list_entry::ListEntryIterator, LdrDataTableEntry, ListEntry, LoaderOrder, UnlinkedModule,
+};
 
 #[repr(C)]
 #[derive(Debug)]
@@ -9,10 +11,59 @@ pub struct PebLdrData {
     reserved2: *const c_void,
     in_load_order_module_list: ListEntry,
     in_memory_order_module_list: ListEntry,
+    in_initialization_order_module_list: ListEntry,
 }
 
 impl PebLdrData {
     pub fn list(self: &Self) -> &ListEntry {
         &self.in_load_order_module_list
     }
+
+    pub fn module_list(self: &Self, order: LoaderOrder) -> &ListEntry {
+        match order {
+            LoaderOrder::Load => &self.in_load_order_module_list,
+            LoaderOrder::Memory => &self.in_memory_order_module_list,
+            LoaderOrder::Initialization => &self.in_initialization_order_module_list,
+        }
+    }
+
+    pub fn iter(self: &'static Self, order: LoaderOrder) -> ListEntryIterator {
+        self.module_list(order).into_iter(order)
+    }
+
+    pub fn contains(self: &'static Self, order: LoaderOrder, entry: &LdrDataTableEntry) -> bool {
+        self.iter(order).any(|listed| core::ptr::eq(listed, entry))
+    }
+
+    /// Modules that are in one list but unlinked from another. The process
+    /// image at `image_base` is never in the initialization order list, nor
+    /// is a DLL whose initialization has not run yet, so those are not
+    /// reported as missing from it.
+    pub fn unlinked_modules(
+        self: &'static Self,
+        image_base: *const c_void,
+    ) -> impl Iterator<Item = UnlinkedModule> {
+        LoaderOrder::ALL.into_iter().flat_map(move |listed_in| {
+            self.iter(listed_in)
+                .filter(move |entry| {
+                    !LoaderOrder::ALL
+                        .into_iter()
+                        .take_while(|order| order.ne(&listed_in))
+                        .any(|order| self.contains(order, entry))
+                })
+                .flat_map(move |entry| {
+                    LoaderOrder::ALL
+                        .into_iter()
+                        .filter(move |missing_from| {
+                            missing_from.ne(&listed_in)
+                                && (missing_from.ne(&LoaderOrder::Initialization)
+                                    || entry.is_initialized(image_base))
+                                && !self.contains(missing_from.clone(), entry)
+                        })
+                        .map(move |missing_from| {
+                            UnlinkedModule::new(entry, listed_in, missing_from)
+                        })
+                })
+        })
+    }
 }
@@ -0,0 +1,203 @@
+use core::mem::offset_of;
+
+use super::{
+    memory_reader::read_value, LdrDataTableEntry32, ListEntry32, MemoryReader, ModuleName,
+};
+
+/// The `PEB_LDR_DATA` of a 32-bit process, such as a WOW64 target.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PebLdrData32 {
+    reserved1: [u8; 8],
+    reserved2: u32,
+    in_load_order_module_list: ListEntry32,
+    in_memory_order_module_list: ListEntry32,
+}
+
+/// Walks the load order list of a 32-bit process through a reader, for at
+/// most [`PebLdrData32::MAX_ENTRIES`] entries.
+pub struct LdrDataTableEntry32Iterator<'r, R: MemoryReader + ?Sized> {
+    reader: &'r R,
+    head: u32,
+    current: u32,
+    remaining: usize,
+}
+
+impl PebLdrData32 {
+    /// The list of another process may be corrupt or change while it is
+    /// walked, and a loop in it must not keep the walk going forever.
+    pub const MAX_ENTRIES: usize = 0x4000;
+
+    /// Reads the loader data at `address`, e.g. [`Peb32::ldr`](super::Peb32::ldr).
+    pub fn read(reader: &(impl MemoryReader + ?Sized), address: u32) -> Option<Self> {
+        read_value(reader, address as u64)
+    }
+
+    pub fn list(self: &Self) -> &ListEntry32 {
+        &self.in_load_order_module_list
+    }
+
+    /// `address` is where this loader data was read from; the list head
+    /// lives inside it.
+    pub fn iter<'r, R: MemoryReader + ?Sized>(
+        self: &Self,
+        reader: &'r R,
+        address: u32,
+    ) -> LdrDataTableEntry32Iterator<'r, R> {
+        // A head past the address space leaves nothing to walk.
+        let head = address.checked_add(offset_of!(PebLdrData32, in_load_order_module_list) as u32);
+        LdrDataTableEntry32Iterator {
+            reader,
+            head: head.unwrap_or(0),
+            current: head.map_or(0, |_| self.in_load_order_module_list.flink().clone()),
+            remaining: PebLdrData32::MAX_ENTRIES,
+        }
+    }
+
+    pub fn find<R: MemoryReader + ?Sized>(
+        self: &Self,
+        reader: &R,
+        address: u32,
+        name: &str,
+    ) -> Option<LdrDataTableEntry32> {
+        let mut buffer = [0u16; LdrDataTableEntry32::MAX_NAME_LENGTH];
+        self.iter(reader, address).find(|entry| {
+            entry
+                .base_dll_name(reader, &mut buffer)
+                .is_some_and(|base_dll_name| {
+                    ModuleName::Utf16(base_dll_name).has_same_base_dll_name(name)
+                })
+        })
+    }
+}
+
+impl<R: MemoryReader + ?Sized> Iterator for LdrDataTableEntry32Iterator<'_, R> {
+    type Item = LdrDataTableEntry32;
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        if self.current.eq(&self.head) || self.current.eq(&0) || self.remaining.eq(&0) {
+            return None;
+        }
+        self.remaining -= 1;
+        let entry = LdrDataTableEntry32::read(self.reader, self.current)?;
+        if entry.dll_base().eq(&0) {
+            return None;
+        }
+        self.current = entry.list().flink().clone();
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::PebLdrData32;
+    use crate::windows::MemoryReader;
+
+    const LDR: u32 = 0x1000;
+    /// `in_load_order_module_list` follows 12 reserved bytes.
+    const HEAD: u32 = LDR + 12;
+    const NAMES: u32 = 0x8000;
+
+    /// 64 KiB of another process' memory from address zero; reads past it
+    /// fail.
+    struct FakeMemory(Vec<u8>);
+
+    impl FakeMemory {
+        fn new() -> Self {
+            Self(vec![0; 0x10000])
+        }
+
+        fn put_u32(self: &mut Self, address: u32, value: u32) {
+            let address = address as usize;
+            self.0[address..address + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        /// The loader data, with its load order list starting at `first`.
+        fn ldr(self: &mut Self, first: u32) {
+            self.put_u32(HEAD, first);
+        }
+
+        /// An entry at `address` linking to `next`, named `name`, whose
+        /// UTF-16 string is stored behind `NAMES`.
+        fn entry(self: &mut Self, address: u32, next: u32, dll_base: u32, name: &str) {
+            self.put_u32(address, next);
+            self.put_u32(address + 24, dll_base);
+            let buffer = NAMES + address / 0x10;
+            let units: Vec<u16> = name.encode_utf16().collect();
+            for (index, unit) in units.iter().enumerate() {
+                let offset = (buffer as usize) + index * 2;
+                self.0[offset..offset + 2].copy_from_slice(&unit.to_le_bytes());
+            }
+            // `base_dll_name`, a `UNICODE_STRING32` at offset 44.
+            let length = (units.len() * 2) as u32;
+            self.put_u32(address + 44, length | (length << 16));
+            self.put_u32(address + 48, buffer);
+        }
+
+        fn read_ldr(self: &Self) -> PebLdrData32 {
+            PebLdrData32::read(self, LDR).unwrap()
+        }
+    }
+
+    impl MemoryReader for FakeMemory {
+        fn read(self: &Self, address: u64, buffer: &mut [u8]) -> Option<()> {
+            let start = usize::try_from(address).ok()?;
+            let bytes = self.0.get(start..start.checked_add(buffer.len())?)?;
+            buffer.copy_from_slice(bytes);
+            Some(())
+        }
+    }
+
+    fn dll_bases(memory: &FakeMemory, address: u32) -> Vec<u32> {
+        memory
+            .read_ldr()
+            .iter(memory, address)
+            .map(|entry| entry.dll_base().clone())
+            .collect()
+    }
+
+    #[test]
+    fn walks_the_load_order_list() {
+        let mut memory = FakeMemory::new();
+        memory.ldr(0x2000);
+        memory.entry(0x2000, 0x3000, 0x7700_0000, "ntdll.dll");
+        memory.entry(0x3000, HEAD, 0x7600_0000, "KERNEL32.DLL");
+
+        assert_eq!(dll_bases(&memory, LDR), vec![0x7700_0000, 0x7600_0000]);
+        let ldr = memory.read_ldr();
+        let kernel32 = ldr.find(&memory, LDR, "kernel32").unwrap();
+        assert_eq!(kernel32.dll_base(), &0x7600_0000);
+        assert!(ldr.find(&memory, LDR, "user32.dll").is_none());
+    }
+
+    #[test]
+    fn stops_in_lists_that_loop() {
+        let mut memory = FakeMemory::new();
+        memory.ldr(0x2000);
+        memory.entry(0x2000, 0x2000, 0x7700_0000, "ntdll.dll");
+
+        let entries = memory.read_ldr().iter(&memory, LDR).count();
+        assert_eq!(entries, PebLdrData32::MAX_ENTRIES);
+    }
+
+    #[test]
+    fn stops_at_unreadable_entries() {
+        let mut memory = FakeMemory::new();
+        memory.ldr(0x2000);
+        memory.entry(0x2000, 0x10_0000, 0x7700_0000, "ntdll.dll");
+
+        assert_eq!(dll_bases(&memory, LDR), vec![0x7700_0000]);
+        assert!(PebLdrData32::read(&memory, 0xfff0).is_none());
+    }
+
+    #[test]
+    fn walks_nothing_when_the_head_overflows() {
+        let mut memory = FakeMemory::new();
+        memory.ldr(0x2000);
+        memory.entry(0x2000, HEAD, 0x7700_0000, "ntdll.dll");
+
+        assert_eq!(dll_bases(&memory, u32::MAX - 4), vec![]);
+    }
+}
@@ -0,0 +1,201 @@
+use core::mem::size_of;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RichHeader<'a> {
+    stub: &'a [u8],
+    offset: usize,
+    entries: &'a [u8],
+    key: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RichEntry {
+    product_id: u16,
+    build: u16,
+    count: u32,
+}
+
+pub struct RichEntryIterator<'a> {
+    entries: core::slice::ChunksExact<'a, u8>,
+    key: u32,
+}
+
+impl<'a> RichHeader<'a> {
+    const DANS: u32 = 0x536e6144;
+    const RICH: u32 = 0x68636952;
+    const LFANEW_OFFSET: usize = 0x3c;
+
+    /// Locates the Rich header in `stub`, the bytes from the start of the
+    /// image up to `e_lfanew`.
+    pub fn parse(stub: &'a [u8]) -> Option<Self> {
+        let dword = |offset: usize| -> Option<u32> {
+            Some(u32::from_le_bytes(
+                stub.get(offset..offset + size_of::<u32>())?
+                    .try_into()
+                    .ok()?,
+            ))
+        };
+
+        let rich_offset = (0..stub.len().saturating_sub(size_of::<u32>()))
+            .step_by(size_of::<u32>())
+            .rev()
+            .find(|offset| dword(offset.clone()).eq(&Some(RichHeader::RICH)))?;
+        let key = dword(rich_offset + size_of::<u32>())?;
+
+        let offset = (0..rich_offset)
+            .step_by(size_of::<u32>())
+            .rev()
+            .find(|offset| {
+                dword(offset.clone()).map(|value| value ^ key) == Some(RichHeader::DANS)
+            })?;
+
+        // DanS is followed by three zero dwords before the first entry.
+        let entries = stub.get(offset + 4 * size_of::<u32>()..rich_offset)?;
+        Some(Self {
+            stub,
+            offset,
+            entries,
+            key,
+        })
+    }
+
+    pub fn offset(self: &Self) -> &usize {
+        &self.offset
+    }
+
+    /// Size in bytes from `DanS` up to and including the checksum dword after `Rich`.
+    pub fn size(self: &Self) -> usize {
+        self.entries.len() + 6 * size_of::<u32>()
+    }
+
+    pub fn key(self: &Self) -> &u32 {
+        &self.key
+    }
+
+    pub fn entries(self: &Self) -> RichEntryIterator<'a> {
+        RichEntryIterator {
+            entries: self.entries.chunks_exact(2 * size_of::<u32>()),
+            key: self.key,
+        }
+    }
+
+    pub fn checksum(self: &Self) -> u32 {
+        let mut checksum = self.offset as u32;
+        for (index, byte) in self.stub[..self.offset].iter().enumerate() {
+            if (RichHeader::LFANEW_OFFSET..RichHeader::LFANEW_OFFSET + size_of::<u32>())
+                .contains(&index)
+            {
+                continue;
+            }
+            checksum = checksum.wrapping_add((byte.clone() as u32).rotate_left(index as u32));
+        }
+        for entry in self.entries() {
+            checksum = checksum.wrapping_add(entry.comp_id().rotate_left(entry.count.clone()));
+        }
+        checksum
+    }
+
+    pub fn is_checksum_valid(self: &Self) -> bool {
+        self.checksum().eq(&self.key)
+    }
+}
+
+impl RichEntry {
+    pub fn product_id(self: &Self) -> &u16 {
+        &self.product_id
+    }
+
+    pub fn build(self: &Self) -> &u16 {
+        &self.build
+    }
+
+    pub fn count(self: &Self) -> &u32 {
+        &self.count
+    }
+
+    pub fn comp_id(self: &Self) -> u32 {
+        ((self.product_id as u32) << 16) | self.build as u32
+    }
+}
+
+impl Iterator for RichEntryIterator<'_> {
+    type Item = RichEntry;
+
+    fn next(self: &mut Self) -> Option<Self::Item> {
+        let entry = self.entries.next()?;
+        let comp_id = u32::from_le_bytes(entry[..4].try_into().ok()?) ^ self.key;
+        let count = u32::from_le_bytes(entry[4..].try_into().ok()?) ^ self.key;
+        Some(RichEntry {
+            product_id: (comp_id >> 16) as u16,
+            build: comp_id as u16,
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RichEntry, RichHeader};
+
+    /// The bytes before `e_lfanew` of an x64 image linked by MSVC.
+    const STUB: [u8; 0xf0] = [
+        0x4d, 0x5a, 0x90, 0x00, 0x03, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0xff, 0xff, 0x00,
+        0x00, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xf0, 0x00, 0x00, 0x00, 0x0e, 0x1f, 0xba, 0x0e, 0x00, 0xb4, 0x09, 0xcd, 0x21, 0xb8, 0x01,
+        0x4c, 0xcd, 0x21, 0x54, 0x68, 0x69, 0x73, 0x20, 0x70, 0x72, 0x6f, 0x67, 0x72, 0x61, 0x6d,
+        0x20, 0x63, 0x61, 0x6e, 0x6e, 0x6f, 0x74, 0x20, 0x62, 0x65, 0x20, 0x72, 0x75, 0x6e, 0x20,
+        0x69, 0x6e, 0x20, 0x44, 0x4f, 0x53, 0x20, 0x6d, 0x6f, 0x64, 0x65, 0x2e, 0x0d, 0x0d, 0x0a,
+        0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xb0, 0x98, 0xdc, 0xad, 0xf4, 0xf9, 0xb2,
+        0xfe, 0xf4, 0xf9, 0xb2, 0xfe, 0xf4, 0xf9, 0xb2, 0xfe, 0x67, 0xb7, 0x2a, 0xfe, 0xf5, 0xf9,
+        0xb2, 0xfe, 0xef, 0x64, 0x19, 0xfe, 0xd5, 0xf9, 0xb2, 0xfe, 0xef, 0x64, 0x18, 0xfe, 0x87,
+        0xf9, 0xb2, 0xfe, 0xef, 0x64, 0x2c, 0xfe, 0xfd, 0xf9, 0xb2, 0xfe, 0xfd, 0x81, 0x21, 0xfe,
+        0xf3, 0xf9, 0xb2, 0xfe, 0xf4, 0xf9, 0xb3, 0xfe, 0x92, 0xf9, 0xb2, 0xfe, 0xef, 0x64, 0x1c,
+        0xfe, 0xf5, 0xf9, 0xb2, 0xfe, 0xef, 0x64, 0x28, 0xfe, 0xf5, 0xf9, 0xb2, 0xfe, 0xef, 0x64,
+        0x2f, 0xfe, 0xf5, 0xf9, 0xb2, 0xfe, 0x52, 0x69, 0x63, 0x68, 0xf4, 0xf9, 0xb2, 0xfe, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn decodes_entries() {
+        let rich_header = RichHeader::parse(&STUB).unwrap();
+        assert_eq!(rich_header.offset(), &0x80);
+        assert_eq!(rich_header.size(), 0xe0 - 0x80);
+        assert_eq!(rich_header.key(), &0xfeb2f9f4);
+        assert_eq!(rich_header.entries().count(), 9);
+        let first = rich_header.entries().next().unwrap();
+        assert_eq!(
+            first,
+            RichEntry {
+                product_id: 0x98,
+                build: 20115,
+                count: 1,
+            }
+        );
+        assert_eq!(first.comp_id(), 0x00984e93);
+    }
+
+    #[test]
+    fn validates_checksum() {
+        let rich_header = RichHeader::parse(&STUB).unwrap();
+        assert_eq!(rich_header.checksum(), 0xfeb2f9f4);
+        assert!(rich_header.is_checksum_valid());
+
+        // e_lfanew is left out of the checksum, the rest of the DOS header
+        // is not.
+        let mut stub = STUB;
+        stub[0x3c] ^= 0xff;
+        assert!(RichHeader::parse(&stub).unwrap().is_checksum_valid());
+        stub[0x4e] ^= 0xff;
+        assert!(!RichHeader::parse(&stub).unwrap().is_checksum_valid());
+    }
+
+    #[test]
+    fn requires_rich_and_dans() {
+        assert!(RichHeader::parse(&STUB[..0x80]).is_none());
+        let mut stub = STUB;
+        stub[0x80] ^= 0xff;
+        assert!(RichHeader::parse(&stub).is_none());
+    }
+}
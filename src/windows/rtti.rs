@@ -0,0 +1,372 @@
+use core::mem::size_of;
+
+#[cfg(any(feature = "alloc", test))]
+use super::ClassHierarchy;
+use super::{
+    Image, ImageSectionHeaders, RttiBaseClassDescriptor, RttiClassHierarchyDescriptor,
+    RttiCompleteObjectLocator, RttiTypeDescriptor,
+};
+
+/// MSVC run-time type information of an image. Only the image-relative
+/// layout emitted for 64-bit targets is understood.
+#[derive(Debug, Clone, Copy)]
+pub struct Rtti<'a> {
+    image: Image<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Vtable<'a> {
+    image: Image<'a>,
+    rva: u32,
+    complete_object_locator: u32,
+}
+
+impl<'a> Rtti<'a> {
+    pub fn new(image: Image<'a>) -> Self {
+        Self { image }
+    }
+
+    pub fn image(self: &Self) -> &Image<'a> {
+        &self.image
+    }
+
+    pub fn complete_object_locator(self: &Self, rva: u32) -> Option<&'a RttiCompleteObjectLocator> {
+        let locator = self.image.at_rva::<RttiCompleteObjectLocator>(rva)?;
+        (locator
+            .signature()
+            .eq(&RttiCompleteObjectLocator::SIGNATURE_IMAGE_RELATIVE)
+            && locator.object_base().eq(&rva))
+        .then_some(locator)
+    }
+
+    pub fn type_descriptor(self: &Self, rva: u32) -> Option<&'a RttiTypeDescriptor> {
+        self.image.at_rva(rva)
+    }
+
+    /// The decorated name of the type descriptor at `rva`, e.g. `.?AVFoo@@`.
+    pub fn type_name(self: &Self, rva: u32) -> Option<&'a str> {
+        self.image.str_at_rva(RttiTypeDescriptor::name_rva(rva))
+    }
+
+    pub fn class_hierarchy_descriptor(
+        self: &Self,
+        rva: u32,
+    ) -> Option<&'a RttiClassHierarchyDescriptor> {
+        self.image.at_rva(rva)
+    }
+
+    /// The base class descriptors of a hierarchy; the first one describes the
+    /// class itself.
+    pub fn base_class_descriptors(
+        self: &Self,
+        class_hierarchy_descriptor: &RttiClassHierarchyDescriptor,
+    ) -> impl Iterator<Item = &'a RttiBaseClassDescriptor> + use<'a> {
+        let image = self.image;
+        image
+            .array_at_rva::<[u8; 4]>(
+                class_hierarchy_descriptor.base_class_array().clone(),
+                class_hierarchy_descriptor.number_of_base_classes().clone() as usize,
+            )
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(move |rva| image.at_rva(u32::from_le_bytes(rva.clone())))
+    }
+
+    /// RVAs of every complete object locator in the non-executable sections.
+    pub fn complete_object_locators(self: &Self) -> impl Iterator<Item = u32> + use<'a> {
+        let rtti = self.clone();
+        self.data_sections().flat_map(move |section| {
+            let virtual_address = section.virtual_address().clone();
+            let data = section.data(&rtti.image).unwrap_or(&[]);
+            data.chunks_exact(size_of::<u32>())
+                .enumerate()
+                .filter(|(_, signature)| {
+                    signature.eq(&RttiCompleteObjectLocator::SIGNATURE_IMAGE_RELATIVE.to_le_bytes())
+                })
+                .map_while(move |(index, _)| {
+                    virtual_address.checked_add(u32::try_from(index * size_of::<u32>()).ok()?)
+                })
+                .filter(move |rva| rtti.complete_object_locator(rva.clone()).is_some())
+        })
+    }
+
+    /// RVAs of every type descriptor, found by their `.?A` decorated names.
+    pub fn type_descriptors(self: &Self) -> impl Iterator<Item = u32> + use<'a> {
+        let rtti = self.clone();
+        let name_offset = size_of::<RttiTypeDescriptor>();
+        self.data_sections().flat_map(move |section| {
+            let virtual_address = section.virtual_address().clone();
+            let data = section.data(&rtti.image).unwrap_or(&[]);
+            data.windows(3)
+                .enumerate()
+                .filter(move |(offset, prefix)| {
+                    prefix.eq(b".?A")
+                        && offset.ge(&name_offset)
+                        && (offset - name_offset).is_multiple_of(size_of::<u64>())
+                })
+                .map_while(move |(offset, _)| {
+                    virtual_address.checked_add(u32::try_from(offset - name_offset).ok()?)
+                })
+                .filter(move |rva| rtti.type_name(rva.clone()).is_some())
+        })
+    }
+
+    /// RVA of the type descriptor of `class_name`, given either as
+    /// `Outer::Inner` or in decorated form.
+    pub fn find_type_descriptor(self: &Self, class_name: &str) -> Option<u32> {
+        self.type_descriptors().find(|rva| {
+            self.type_name(rva.clone()).is_some_and(|decorated_name| {
+                RttiTypeDescriptor::is_decorated_name_of(decorated_name, class_name)
+            })
+        })
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub fn class_hierarchy(self: &Self) -> ClassHierarchy<'a> {
+        ClassHierarchy::new(self)
+    }
+
+    /// The primary vtable of `class_name`, the one at offset zero of the
+    /// complete object.
+    pub fn find_vtable(self: &Self, class_name: &str) -> Option<Vtable<'a>> {
+        let type_descriptor = self.find_type_descriptor(class_name)?;
+        self.complete_object_locators()
+            .find(|rva| {
+                self.complete_object_locator(rva.clone())
+                    .is_some_and(|locator| {
+                        locator.type_descriptor().eq(&type_descriptor) && locator.offset().eq(&0)
+                    })
+            })
+            .and_then(|rva| self.vtable_of(rva))
+    }
+
+    /// The vtable whose slot `-1` points at the locator at `rva`.
+    pub fn vtable_of(self: &Self, complete_object_locator: u32) -> Option<Vtable<'a>> {
+        let locator_address = self.image.rva_to_address(complete_object_locator)?;
+        self.data_sections().find_map(|section| {
+            let virtual_address = section.virtual_address().clone();
+            let data = section.data(&self.image)?;
+            let index = data.chunks_exact(size_of::<u64>()).position(|slot| {
+                u64::from_le_bytes(slot.try_into().unwrap()).eq(&locator_address)
+            })?;
+            let offset = u32::try_from((index + 1) * size_of::<u64>()).ok()?;
+            Some(Vtable {
+                image: self.image,
+                rva: virtual_address.checked_add(offset)?,
+                complete_object_locator,
+            })
+        })
+    }
+
+    fn data_sections(self: &Self) -> impl Iterator<Item = &'a ImageSectionHeaders> + use<'a> {
+        self.image
+            .section_headers()
+            .iter()
+            .filter(|section| section.is_readable() && !section.is_executable())
+    }
+}
+
+impl<'a> Vtable<'a> {
+    pub fn rva(self: &Self) -> &u32 {
+        &self.rva
+    }
+
+    pub fn address(self: &Self) -> Option<u64> {
+        self.image.rva_to_address(self.rva)
+    }
+
+    pub fn complete_object_locator(self: &Self) -> &u32 {
+        &self.complete_object_locator
+    }
+
+    /// Absolute addresses of the virtual methods, read until the first slot
+    /// that does not point into an executable section.
+    pub fn methods(self: &Self) -> impl Iterator<Item = u64> + use<'a> {
+        let image = self.image;
+        (self.rva..)
+            .step_by(size_of::<u64>())
+            .map_while(move |rva| image.read_at_rva::<u64>(rva))
+            .take_while(move |address| {
+                image
+                    .address_to_rva(address.clone())
+                    .and_then(|rva| image.section_containing_rva(rva))
+                    .is_some_and(|section| section.is_executable())
+            })
+    }
+
+    pub fn method_rvas(self: &Self) -> impl Iterator<Item = u32> + use<'a> {
+        let image = self.image;
+        self.methods()
+            .filter_map(move |address| image.address_to_rva(address))
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.methods().count()
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.methods().next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::Rtti;
+    use crate::windows::{
+        test_image::{TestImage, TestRtti},
+        Image,
+    };
+
+    const IMAGE_BASE: u64 = 0x1_8000_0000;
+    const RDATA_RVA: u32 = 0x2000;
+
+    /// `Base` with two methods and `Outer::Derived` deriving from it with
+    /// three, for an image at `image_base`.
+    fn rtti(image_base: u64) -> TestRtti {
+        TestRtti::new(RDATA_RVA, image_base)
+            .class(".?AVBase@@", &[], &[0x1800, 0x1810])
+            .class(".?AVDerived@Outer@@", &[0], &[0x1800, 0x1820, 0x1830])
+    }
+
+    /// The RTTI in a `.rdata` section behind the code.
+    fn image(rtti: &TestRtti) -> TestImage {
+        TestImage::new("rtti.dll")
+            .image_base(IMAGE_BASE)
+            .section(b".rdata", RDATA_RVA, 0x4000_0040)
+            .contents(RDATA_RVA, rtti.bytes())
+    }
+
+    #[test]
+    fn finds_rtti_structures() {
+        let test_rtti = rtti(IMAGE_BASE);
+        let bytes = image(&test_rtti).build();
+        let rtti = Rtti::new(Image::from_file(&bytes).unwrap());
+        let [base, derived] = test_rtti.classes() else {
+            unreachable!()
+        };
+
+        let locators: Vec<u32> = rtti.complete_object_locators().collect();
+        assert_eq!(
+            locators,
+            vec![
+                base.complete_object_locator,
+                derived.complete_object_locator
+            ]
+        );
+        let type_descriptors: Vec<u32> = rtti.type_descriptors().collect();
+        assert_eq!(
+            type_descriptors,
+            vec![base.type_descriptor, derived.type_descriptor]
+        );
+        assert_eq!(
+            rtti.type_name(derived.type_descriptor),
+            Some(".?AVDerived@Outer@@")
+        );
+
+        let locator = rtti
+            .complete_object_locator(derived.complete_object_locator)
+            .unwrap();
+        assert_eq!(locator.type_descriptor(), derived.type_descriptor);
+        assert_eq!(
+            locator.class_descriptor(),
+            derived.class_hierarchy_descriptor
+        );
+
+        // The class itself comes first, then its base.
+        let class_hierarchy_descriptor = rtti
+            .class_hierarchy_descriptor(locator.class_descriptor())
+            .unwrap();
+        let descriptors: Vec<(u32, u32)> = rtti
+            .base_class_descriptors(class_hierarchy_descriptor)
+            .map(|descriptor| {
+                (
+                    descriptor.type_descriptor(),
+                    descriptor.number_of_contained_bases(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            descriptors,
+            vec![(derived.type_descriptor, 1), (base.type_descriptor, 0)]
+        );
+
+        // Only the locators themselves carry their own RVA.
+        assert!(rtti
+            .complete_object_locator(derived.class_hierarchy_descriptor)
+            .is_none());
+    }
+
+    #[test]
+    fn finds_vtables_by_class_name() {
+        let test_rtti = rtti(IMAGE_BASE);
+        let file = image(&test_rtti).raw_data(0x400, 0x2000).build();
+        let mut mapped = image(&test_rtti).build_mapped();
+        // Mapped images live at their buffer, where the loader would have
+        // relocated the vtable slots to.
+        let relocated = rtti(mapped.as_ptr() as u64);
+        let rdata = RDATA_RVA as usize;
+        mapped[rdata..rdata + relocated.bytes().len()].copy_from_slice(relocated.bytes());
+        let [base, derived] = test_rtti.classes() else {
+            unreachable!()
+        };
+
+        let images = [
+            (Image::from_file(&file), IMAGE_BASE),
+            (Image::from_mapped(&mapped), mapped.as_ptr() as u64),
+        ];
+        for (image, image_base) in images {
+            let rtti = Rtti::new(image.unwrap());
+            for class_name in ["Outer::Derived", ".?AVDerived@Outer@@"] {
+                let vtable = rtti.find_vtable(class_name).unwrap();
+                assert_eq!(vtable.rva(), &derived.vtable);
+                assert_eq!(
+                    vtable.complete_object_locator(),
+                    &derived.complete_object_locator
+                );
+                assert_eq!(vtable.address(), Some(image_base + derived.vtable as u64));
+                let methods: Vec<u32> = vtable.method_rvas().collect();
+                assert_eq!(methods, vec![0x1800, 0x1820, 0x1830]);
+            }
+
+            let vtable = rtti.find_vtable("Base").unwrap();
+            assert_eq!(vtable.rva(), &base.vtable);
+            assert_eq!(vtable.len(), 2);
+
+            assert!(rtti.find_vtable("Derived").is_none());
+            assert!(rtti.find_vtable("Missing").is_none());
+        }
+    }
+
+    #[test]
+    fn vtable_of_round_trips() {
+        let test_rtti = rtti(IMAGE_BASE);
+        let bytes = image(&test_rtti).build();
+        let rtti = Rtti::new(Image::from_file(&bytes).unwrap());
+
+        for class in test_rtti.classes() {
+            let vtable = rtti.vtable_of(class.complete_object_locator).unwrap();
+            assert_eq!(vtable.rva(), &class.vtable);
+            let locator = vtable.complete_object_locator().clone();
+            assert_eq!(locator, class.complete_object_locator);
+            assert_eq!(rtti.vtable_of(locator).unwrap().rva(), vtable.rva());
+        }
+        // Nothing points at a type descriptor.
+        assert!(rtti
+            .vtable_of(test_rtti.classes()[0].type_descriptor)
+            .is_none());
+    }
+
+    #[test]
+    fn ignores_rtti_in_executable_sections() {
+        let test_rtti = rtti(IMAGE_BASE);
+        let bytes = TestImage::new("rtti.dll")
+            .image_base(IMAGE_BASE)
+            .contents(RDATA_RVA, test_rtti.bytes())
+            .build();
+        let rtti = Rtti::new(Image::from_file(&bytes).unwrap());
+        assert_eq!(rtti.complete_object_locators().count(), 0);
+        assert!(rtti.find_vtable("Base").is_none());
+    }
+}
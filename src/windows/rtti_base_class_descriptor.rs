@@ -0,0 +1,76 @@
+use core::ops::BitAnd;
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct RttiBaseClassDescriptor {
+    type_descriptor: u32,
+    number_of_contained_bases: u32,
+    pmd: RttiPmd,
+    attributes: u32,
+    class_descriptor: u32,
+}
+
+/// Where a base class sits within the derived object: `mdisp` is the offset
+/// of the base, and when `pdisp` is not `-1` the base is virtual and its
+/// offset is read from the vbtable at `pdisp`, entry `vdisp`.
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct RttiPmd {
+    mdisp: i32,
+    pdisp: i32,
+    vdisp: i32,
+}
+
+#[derive(Debug)]
+pub enum RttiBaseClass {
+    NotVisible = 0x01,
+    Ambiguous = 0x02,
+    PrivOrProtBase = 0x04,
+    PrivOrProtEmbed = 0x08,
+    NonPolymorphic = 0x10,
+    HasHierarchyDescriptor = 0x40,
+}
+
+impl RttiBaseClassDescriptor {
+    pub fn type_descriptor(self: &Self) -> u32 {
+        self.type_descriptor
+    }
+
+    pub fn number_of_contained_bases(self: &Self) -> u32 {
+        self.number_of_contained_bases
+    }
+
+    pub fn pmd(self: &Self) -> &RttiPmd {
+        &self.pmd
+    }
+
+    pub fn attributes(self: &Self) -> u32 {
+        self.attributes
+    }
+
+    pub fn has_attribute(self: &Self, attribute: RttiBaseClass) -> bool {
+        self.attributes.bitand(attribute as u32).ne(&0)
+    }
+
+    pub fn class_descriptor(self: &Self) -> u32 {
+        self.class_descriptor
+    }
+
+    pub fn is_virtual(self: &Self) -> bool {
+        self.pmd.pdisp().ne(&-1)
+    }
+}
+
+impl RttiPmd {
+    pub fn mdisp(self: &Self) -> i32 {
+        self.mdisp
+    }
+
+    pub fn pdisp(self: &Self) -> i32 {
+        self.pdisp
+    }
+
+    pub fn vdisp(self: &Self) -> i32 {
+        self.vdisp
+    }
+}
@@ -0,0 +1,41 @@
+use core::ops::BitAnd;
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct RttiClassHierarchyDescriptor {
+    signature: u32,
+    attributes: u32,
+    number_of_base_classes: u32,
+    base_class_array: u32,
+}
+
+#[derive(Debug)]
+pub enum RttiInheritance {
+    Multiple = 0x01,
+    Virtual = 0x02,
+    Ambiguous = 0x04,
+}
+
+impl RttiClassHierarchyDescriptor {
+    pub fn signature(self: &Self) -> u32 {
+        self.signature
+    }
+
+    pub fn attributes(self: &Self) -> u32 {
+        self.attributes
+    }
+
+    pub fn has_attribute(self: &Self, attribute: RttiInheritance) -> bool {
+        self.attributes.bitand(attribute as u32).ne(&0)
+    }
+
+    /// Number of entries in the base class array, the class itself included.
+    pub fn number_of_base_classes(self: &Self) -> u32 {
+        self.number_of_base_classes
+    }
+
+    /// RVA of an array of RVAs to [`RttiBaseClassDescriptor`](super::RttiBaseClassDescriptor)s.
+    pub fn base_class_array(self: &Self) -> u32 {
+        self.base_class_array
+    }
+}
@@ -0,0 +1,42 @@
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct RttiCompleteObjectLocator {
+    signature: u32,
+    offset: u32,
+    cd_offset: u32,
+    type_descriptor: u32,
+    class_descriptor: u32,
+    object_base: u32,
+}
+
+impl RttiCompleteObjectLocator {
+    /// Locators of 64-bit images hold RVAs instead of pointers and mark it
+    /// with this signature.
+    pub const SIGNATURE_IMAGE_RELATIVE: u32 = 1;
+
+    pub fn signature(self: &Self) -> u32 {
+        self.signature
+    }
+
+    /// Offset of the vtable pointer within the complete object.
+    pub fn offset(self: &Self) -> u32 {
+        self.offset
+    }
+
+    pub fn cd_offset(self: &Self) -> u32 {
+        self.cd_offset
+    }
+
+    pub fn type_descriptor(self: &Self) -> u32 {
+        self.type_descriptor
+    }
+
+    pub fn class_descriptor(self: &Self) -> u32 {
+        self.class_descriptor
+    }
+
+    /// RVA of the locator itself, used to find the image base at runtime.
+    pub fn object_base(self: &Self) -> u32 {
+        self.object_base
+    }
+}
@@ -0,0 +1,35 @@
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct RttiTypeDescriptor {
+    vftable: u64,
+    spare: u64,
+}
+
+impl RttiTypeDescriptor {
+    /// Absolute address of the `type_info` vtable.
+    pub fn vftable(self: &Self) -> u64 {
+        self.vftable
+    }
+
+    /// RVA of the NUL terminated decorated name that follows the descriptor.
+    pub fn name_rva(rva: u32) -> u32 {
+        rva.saturating_add(core::mem::size_of::<RttiTypeDescriptor>() as u32)
+    }
+
+    /// Whether a decorated name such as `.?AVInner@Outer@@` names the class
+    /// `Outer::Inner`. Names already in decorated form compare verbatim.
+    pub fn is_decorated_name_of(decorated_name: &str, class_name: &str) -> bool {
+        if class_name.starts_with(".?A") {
+            return decorated_name.eq(class_name);
+        }
+        let body = match decorated_name
+            .strip_prefix(".?AV")
+            .or_else(|| decorated_name.strip_prefix(".?AU"))
+            .and_then(|body| body.strip_suffix("@@"))
+        {
+            Some(body) => body,
+            None => return false,
+        };
+        body.split('@').eq(class_name.rsplit("::"))
+    }
+}
@@ -0,0 +1,299 @@
+use super::{
+    Image, ImageCetCharacteristics, ImageDebugType, ImageDirectoryEntry, ImageDllCharacteristics,
+    ImageFile, ImageGuard, ImageOptionalHeader, ImageSectionHeaders,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SecurityReport<'a> {
+    aslr: bool,
+    high_entropy_va: bool,
+    dep: bool,
+    cfg: bool,
+    safe_seh: Option<bool>,
+    gs: bool,
+    cet_compatible: bool,
+    signed: bool,
+    section_headers: &'a [ImageSectionHeaders],
+}
+
+impl<'a> SecurityReport<'a> {
+    pub fn new(image: &Image<'a>) -> Self {
+        let optional_header = image.optional_header();
+        let load_config_directory = image.load_config_directory();
+
+        let aslr = optional_header.has_dll_characteristic(ImageDllCharacteristics::DynamicBase)
+            && image
+                .data_directory(ImageDirectoryEntry::BaseReloc)
+                .is_present()
+            && !image
+                .file_header()
+                .has_characteristic(ImageFile::RelocsStripped);
+
+        let high_entropy_va = aslr
+            && optional_header.magic().eq(&ImageOptionalHeader::MAGIC64)
+            && optional_header.has_dll_characteristic(ImageDllCharacteristics::HighEntropyVa);
+
+        let cfg = optional_header.has_dll_characteristic(ImageDllCharacteristics::GuardCf)
+            && load_config_directory
+                .is_some_and(|directory| directory.has_guard_flag(ImageGuard::CfInstrumented));
+
+        // Table based exception handling makes SafeSEH meaningless outside of PE32 images.
+        let safe_seh = optional_header
+            .magic()
+            .eq(&ImageOptionalHeader::MAGIC32)
+            .then(|| {
+                optional_header.has_dll_characteristic(ImageDllCharacteristics::NoSeh)
+                    || load_config_directory.is_some_and(|directory| {
+                        directory
+                            .se_handler_table()
+                            .is_some_and(|table| table.ne(&0))
+                            && directory
+                                .se_handler_count()
+                                .is_some_and(|count| count.ne(&0))
+                    })
+            });
+
+        let gs = load_config_directory
+            .and_then(|directory| directory.security_cookie())
+            .is_some_and(|security_cookie| security_cookie.ne(&0));
+
+        let cet_compatible = image
+            .debug_directories()
+            .iter()
+            .filter(|directory| directory.is_type(ImageDebugType::ExDllCharacteristics))
+            .filter_map(|directory| image.debug_data(directory)?.first_chunk::<4>())
+            .any(|data| {
+                u32::from_le_bytes(data.clone()) & (ImageCetCharacteristics::Compat as u32) != 0
+            });
+
+        Self {
+            aslr,
+            high_entropy_va,
+            dep: optional_header.has_dll_characteristic(ImageDllCharacteristics::NxCompat),
+            cfg,
+            safe_seh,
+            gs,
+            cet_compatible,
+            signed: image
+                .data_directory(ImageDirectoryEntry::Security)
+                .is_present(),
+            section_headers: image.section_headers(),
+        }
+    }
+
+    pub fn has_aslr(self: &Self) -> bool {
+        self.aslr
+    }
+
+    pub fn has_high_entropy_va(self: &Self) -> bool {
+        self.high_entropy_va
+    }
+
+    pub fn has_dep(self: &Self) -> bool {
+        self.dep
+    }
+
+    pub fn has_cfg(self: &Self) -> bool {
+        self.cfg
+    }
+
+    /// `None` when the image is not PE32, where SafeSEH does not apply.
+    pub fn has_safe_seh(self: &Self) -> Option<bool> {
+        self.safe_seh
+    }
+
+    pub fn has_gs(self: &Self) -> bool {
+        self.gs
+    }
+
+    pub fn is_cet_compatible(self: &Self) -> bool {
+        self.cet_compatible
+    }
+
+    /// Only reports whether a certificate table is present; the signature
+    /// itself is not verified.
+    pub fn is_signed(self: &Self) -> bool {
+        self.signed
+    }
+
+    pub fn writable_executable_sections(
+        self: &Self,
+    ) -> impl Iterator<Item = &'a ImageSectionHeaders> {
+        self.section_headers
+            .iter()
+            .filter(|section| section.is_writable() && section.is_executable())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::SecurityReport;
+    use crate::windows::{
+        test_image::TestImage, Image, ImageDirectoryEntry, ImageDllCharacteristics, ImageGuard,
+    };
+
+    /// An `IMAGE_LOAD_CONFIG_DIRECTORY64` of `size` bytes with a security
+    /// cookie and `guard_flags`.
+    fn load_config(size: u32, guard_flags: u32) -> Vec<u8> {
+        let mut bytes = vec![0; 148];
+        bytes[0..4].copy_from_slice(&size.to_le_bytes());
+        bytes[88..96].copy_from_slice(&0x2b99_2ddf_a232u64.to_le_bytes());
+        bytes[144..148].copy_from_slice(&guard_flags.to_le_bytes());
+        bytes.truncate(size as usize);
+        bytes
+    }
+
+    fn report<R>(test_image: TestImage, check: impl FnOnce(SecurityReport) -> R) -> R {
+        let bytes = test_image.build();
+        check(Image::from_file(&bytes).unwrap().security_report())
+    }
+
+    fn flags<const N: usize>(characteristics: [ImageDllCharacteristics; N]) -> u16 {
+        characteristics
+            .into_iter()
+            .fold(0, |flags, characteristic| flags | characteristic as u16)
+    }
+
+    #[test]
+    fn reports_nothing_for_a_bare_image() {
+        report(TestImage::new("sample.dll"), |report| {
+            assert!(!report.has_aslr());
+            assert!(!report.has_high_entropy_va());
+            assert!(!report.has_dep());
+            assert!(!report.has_cfg());
+            assert!(!report.has_gs());
+            assert!(!report.is_cet_compatible());
+            assert!(!report.is_signed());
+            // PE32+ images have no SafeSEH.
+            assert_eq!(report.has_safe_seh(), None);
+            assert_eq!(report.writable_executable_sections().count(), 0);
+        });
+    }
+
+    #[test]
+    fn signed_images_have_a_certificate_table() {
+        let signed = TestImage::new("sample.dll").data_directory(
+            ImageDirectoryEntry::Security,
+            TestImage::SIZE_OF_IMAGE,
+            0x400,
+        );
+        report(signed, |report| assert!(report.is_signed()));
+    }
+
+    #[test]
+    fn reads_dll_characteristics() {
+        use ImageDllCharacteristics::*;
+
+        let all = flags([DynamicBase, HighEntropyVa, NxCompat, GuardCf]);
+        let image = TestImage::new("sample.dll").dll_characteristics(all);
+        report(image, |report| {
+            assert!(report.has_dep());
+            // ASLR needs relocations, CFG a load config.
+            assert!(!report.has_aslr());
+            assert!(!report.has_high_entropy_va());
+            assert!(!report.has_cfg());
+        });
+
+        let relocatable = |characteristics| {
+            TestImage::new("sample.dll")
+                .dll_characteristics(characteristics)
+                .data_directory(ImageDirectoryEntry::BaseReloc, 0x1800, 12)
+        };
+        report(relocatable(all), |report| {
+            assert!(report.has_aslr());
+            assert!(report.has_high_entropy_va());
+        });
+        report(relocatable(flags([DynamicBase])), |report| {
+            assert!(report.has_aslr());
+            assert!(!report.has_high_entropy_va());
+            assert!(!report.has_dep());
+        });
+        report(relocatable(flags([HighEntropyVa])), |report| {
+            assert!(!report.has_aslr());
+            assert!(!report.has_high_entropy_va());
+        });
+    }
+
+    #[test]
+    fn reads_the_load_config() {
+        let cf_instrumented = ImageGuard::CfInstrumented as u32;
+        let with_load_config = |characteristics, load_config: &[u8]| {
+            TestImage::new("sample.dll")
+                .dll_characteristics(characteristics)
+                .contents(0x1800, load_config)
+                .data_directory(
+                    ImageDirectoryEntry::LoadConfig,
+                    0x1800,
+                    load_config.len() as u32,
+                )
+                .raw_data(0x400, 0x2000)
+        };
+        let guard_cf = flags([ImageDllCharacteristics::GuardCf]);
+
+        report(
+            with_load_config(guard_cf, &load_config(148, cf_instrumented)),
+            |report| {
+                assert!(report.has_cfg());
+                assert!(report.has_gs());
+            },
+        );
+        // CFG needs both the flag in the header and the instrumented code.
+        report(
+            with_load_config(0, &load_config(148, cf_instrumented)),
+            |report| {
+                assert!(!report.has_cfg());
+                assert!(report.has_gs());
+            },
+        );
+        report(with_load_config(guard_cf, &load_config(148, 0)), |report| {
+            assert!(!report.has_cfg());
+        });
+        // Directories predating a field leave it out.
+        report(
+            with_load_config(guard_cf, &load_config(88, cf_instrumented)),
+            |report| {
+                assert!(!report.has_cfg());
+                assert!(!report.has_gs());
+            },
+        );
+        report(
+            with_load_config(guard_cf, &load_config(96, cf_instrumented)),
+            |report| {
+                assert!(!report.has_cfg());
+                assert!(report.has_gs());
+            },
+        );
+    }
+
+    #[test]
+    fn ignores_directories_past_their_count() {
+        // With four directories the Security slot holds the section header.
+        let image = TestImage::new("sample.dll").number_of_rva_and_sizes(4);
+        let bytes = image.build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert_eq!(image.section_headers().len(), 1);
+        assert_eq!(image.optional_header().number_of_rva_and_sizes(), 4);
+        assert!(!image
+            .data_directory(ImageDirectoryEntry::Security)
+            .is_present());
+        assert!(image.load_config_directory().is_none());
+        assert!(image.debug_directories().is_empty());
+
+        let report = image.security_report();
+        assert!(!report.is_signed());
+        assert!(!report.has_cfg());
+        assert!(!report.has_gs());
+        assert!(!report.is_cet_compatible());
+
+        // The directories within the count still count.
+        let bytes = TestImage::new("sample.dll")
+            .number_of_rva_and_sizes(6)
+            .dll_characteristics(flags([ImageDllCharacteristics::DynamicBase]))
+            .data_directory(ImageDirectoryEntry::BaseReloc, 0x1800, 12)
+            .build();
+        let image = Image::from_file(&bytes).unwrap();
+        assert!(image.security_report().has_aslr());
+    }
+}
@@ -0,0 +1,594 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use super::{ImageDirectoryEntry, ImageMachine};
+
+/// Builds minimal PE32+ images with an export table for tests. Unless moved
+/// with [`TestImage::raw_data`], the sections start at the same RVA
+/// and file offset, so the bytes of [`TestImage::build`] are valid both as a
+/// file and as a mapped image.
+pub struct TestImage {
+    dll_name: String,
+    functions: Vec<(Option<String>, TestExport)>,
+    ordinal_base: u32,
+    machine: ImageMachine,
+    time_date_stamp: u32,
+    image_base: u64,
+    dll_characteristics: u16,
+    number_of_rva_and_sizes: u32,
+    section_name: Vec<u8>,
+    section_characteristics: u32,
+    sections: Vec<(Vec<u8>, u32, u32)>,
+    string_table: Option<Vec<u8>>,
+    data_directories: Vec<(usize, u32, u32)>,
+    contents: Vec<(u32, Vec<u8>)>,
+    raw_offset: u32,
+    raw_size: u32,
+    dos_stub: Vec<u8>,
+    overlay: Vec<u8>,
+}
+
+enum TestExport {
+    Code(u32),
+    Forwarder(String),
+}
+
+impl TestImage {
+    pub const SECTION_RVA: u32 = 0x1000;
+    /// Code RVAs handed to [`TestImage::export`] should lie at or above
+    /// this and below [`TestImage::SIZE_OF_IMAGE`].
+    pub const CODE_RVA: u32 = 0x2000;
+    pub const SIZE_OF_IMAGE: u32 = 0x3000;
+    /// Unless changed with [`TestImage::ordinal_base`].
+    pub const ORDINAL_BASE: u32 = 1;
+
+    pub fn new(dll_name: &str) -> Self {
+        Self {
+            dll_name: String::from(dll_name),
+            functions: Vec::new(),
+            ordinal_base: TestImage::ORDINAL_BASE,
+            machine: ImageMachine::Amd64,
+            time_date_stamp: 0,
+            image_base: 0x1_8000_0000,
+            dll_characteristics: 0,
+            number_of_rva_and_sizes: 16,
+            section_name: Vec::from(&b".text"[..]),
+            section_characteristics: 0x6000_0020,
+            sections: Vec::new(),
+            string_table: None,
+            data_directories: Vec::new(),
+            contents: Vec::new(),
+            raw_offset: TestImage::SECTION_RVA,
+            raw_size: TestImage::SIZE_OF_IMAGE - TestImage::SECTION_RVA,
+            dos_stub: Vec::new(),
+            overlay: Vec::new(),
+        }
+    }
+
+    pub fn export(mut self: Self, name: &str, rva: u32) -> Self {
+        self.functions
+            .push((Some(String::from(name)), TestExport::Code(rva)));
+        self
+    }
+
+    /// An export without a name, at the next ordinal.
+    pub fn export_by_ordinal(mut self: Self, rva: u32) -> Self {
+        self.functions.push((None, TestExport::Code(rva)));
+        self
+    }
+
+    /// `forwarder` as in `module.function` or `module.#ordinal`.
+    pub fn forward(mut self: Self, name: &str, forwarder: &str) -> Self {
+        self.functions.push((
+            Some(String::from(name)),
+            TestExport::Forwarder(String::from(forwarder)),
+        ));
+        self
+    }
+
+    pub fn ordinal_base(mut self: Self, ordinal_base: u32) -> Self {
+        self.ordinal_base = ordinal_base;
+        self
+    }
+
+    pub fn machine(mut self: Self, machine: ImageMachine) -> Self {
+        self.machine = machine;
+        self
+    }
+
+    pub fn time_date_stamp(mut self: Self, time_date_stamp: u32) -> Self {
+        self.time_date_stamp = time_date_stamp;
+        self
+    }
+
+    pub fn image_base(mut self: Self, image_base: u64) -> Self {
+        self.image_base = image_base;
+        self
+    }
+
+    pub fn dll_characteristics(mut self: Self, dll_characteristics: u16) -> Self {
+        self.dll_characteristics = dll_characteristics;
+        self
+    }
+
+    /// Shortens the optional header to `count` data directories, moving the
+    /// section table up behind them.
+    pub fn number_of_rva_and_sizes(mut self: Self, count: u32) -> Self {
+        // The export directory is always there.
+        assert!(count.ge(&1) && count.le(&16));
+        self.number_of_rva_and_sizes = count;
+        self
+    }
+
+    /// Points `entry` at `size` bytes at `rva`, usually placed with
+    /// [`TestImage::contents`].
+    pub fn data_directory(mut self: Self, entry: ImageDirectoryEntry, rva: u32, size: u32) -> Self {
+        self.data_directories.push((entry as usize, rva, size));
+        self
+    }
+
+    /// Places `bytes` at `rva` in the section, after the export data and
+    /// below [`TestImage::SIZE_OF_IMAGE`].
+    pub fn contents(mut self: Self, rva: u32, bytes: &[u8]) -> Self {
+        self.contents.push((rva, Vec::from(bytes)));
+        self
+    }
+
+    /// Stores the section at file `offset`, a multiple of 0x200, with only
+    /// its first `size` bytes on disk. The loader zero-fills the rest.
+    pub fn raw_data(mut self: Self, offset: u32, size: u32) -> Self {
+        assert!(offset.ge(&0x200) && offset.is_multiple_of(0x200));
+        assert!(size.le(&(TestImage::SIZE_OF_IMAGE - TestImage::SECTION_RVA)));
+        self.raw_offset = offset;
+        self.raw_size = size;
+        self
+    }
+
+    /// Puts `stub` between the DOS header and the NT headers, which move up
+    /// to the next 8-byte boundary behind it.
+    pub fn dos_stub(mut self: Self, stub: &[u8]) -> Self {
+        self.dos_stub = Vec::from(stub);
+        self
+    }
+
+    /// Appends `overlay` to the file behind the raw data and string table.
+    pub fn overlay(mut self: Self, overlay: &[u8]) -> Self {
+        self.overlay = Vec::from(overlay);
+        self
+    }
+
+    /// Names the section, e.g. `/4` for a long name in the string table.
+    pub fn section_name(mut self: Self, name: &[u8]) -> Self {
+        assert!(name.len().le(&8));
+        self.section_name = Vec::from(name);
+        self
+    }
+
+    /// `IMAGE_SCN_*` flags of the section, executable code by default.
+    pub fn section_characteristics(mut self: Self, characteristics: u32) -> Self {
+        self.section_characteristics = characteristics;
+        self
+    }
+
+    /// Splits off a section from `rva` up to the next section or the end of
+    /// the image. Sections are added in ascending order and share the raw
+    /// data of the first one, which keeps the exports.
+    pub fn section(mut self: Self, name: &[u8], rva: u32, characteristics: u32) -> Self {
+        let previous = self
+            .sections
+            .last()
+            .map_or(TestImage::SECTION_RVA, |(_, rva, _)| rva.clone());
+        assert!(name.len().le(&8) && rva.gt(&previous) && rva.lt(&TestImage::SIZE_OF_IMAGE));
+        self.sections.push((Vec::from(name), rva, characteristics));
+        self
+    }
+
+    /// Appends a COFF string table holding `strings` to the file. There are
+    /// no symbols, so the table starts at the symbol table pointer and
+    /// `strings` start at offset 4, after the table's size.
+    pub fn string_table(mut self: Self, strings: &[u8]) -> Self {
+        self.string_table = Some(Vec::from(strings));
+        self
+    }
+
+    /// The image as stored on disk.
+    pub fn build(self: &Self) -> Vec<u8> {
+        let mapped = self.build_mapped();
+        let size_of_headers = self.size_of_headers() as usize;
+        let raw_offset = self.raw_offset.clone() as usize;
+        let raw_size = self.raw_size.clone() as usize;
+        let section = TestImage::SECTION_RVA as usize;
+
+        let mut bytes = vec![0u8; raw_offset + raw_size];
+        put(&mut bytes, 0, &mapped[..size_of_headers]);
+        put(&mut bytes, raw_offset, &mapped[section..section + raw_size]);
+
+        if let Some(string_table) = &self.string_table {
+            let pointer_to_symbol_table = bytes.len() as u32;
+            put_u32(&mut bytes, self.file_header() + 8, pointer_to_symbol_table);
+            let size = string_table.len() as u32 + 4;
+            bytes.extend_from_slice(&size.to_le_bytes());
+            bytes.extend_from_slice(string_table);
+        }
+        bytes.extend_from_slice(&self.overlay);
+        bytes
+    }
+
+    fn file_header(self: &Self) -> usize {
+        0x44 + self.dos_stub.len().next_multiple_of(8)
+    }
+
+    fn size_of_headers(self: &Self) -> u32 {
+        self.raw_offset.min(TestImage::SECTION_RVA)
+    }
+
+    /// The image as mapped by the loader.
+    pub fn build_mapped(self: &Self) -> Vec<u8> {
+        let mut bytes = vec![0u8; TestImage::SIZE_OF_IMAGE as usize];
+
+        // DOS header and stub, `e_lfanew` pointing right after them.
+        let nt_headers = self.file_header() - 4;
+        put(&mut bytes, 0, b"MZ");
+        put_u32(&mut bytes, 0x3c, nt_headers as u32);
+        put(&mut bytes, 0x40, &self.dos_stub);
+
+        // NT headers.
+        put(&mut bytes, nt_headers, b"PE\0\0");
+        let file_header = self.file_header();
+        put_u16(&mut bytes, file_header, self.machine.clone() as u16);
+        let number_of_sections = self.sections.len() + 1;
+        put_u16(&mut bytes, file_header + 2, number_of_sections as u16);
+        put_u32(&mut bytes, file_header + 4, self.time_date_stamp.clone());
+        let data_directories = self.number_of_rva_and_sizes.clone() as usize;
+        let size_of_optional_header = 112 + data_directories * 8;
+        put_u16(&mut bytes, file_header + 16, size_of_optional_header as u16);
+        put_u16(&mut bytes, file_header + 18, 0x2022);
+
+        let optional_header = file_header + 20;
+        put_u16(&mut bytes, optional_header, 0x20b);
+        put_u64(&mut bytes, optional_header + 24, self.image_base.clone());
+        put_u32(&mut bytes, optional_header + 32, 0x1000);
+        put_u32(&mut bytes, optional_header + 36, 0x200);
+        put_u32(&mut bytes, optional_header + 56, TestImage::SIZE_OF_IMAGE);
+        put_u32(&mut bytes, optional_header + 60, self.size_of_headers());
+        put_u16(
+            &mut bytes,
+            optional_header + 70,
+            self.dll_characteristics.clone(),
+        );
+        put_u32(&mut bytes, optional_header + 108, data_directories as u32);
+
+        let first = (
+            &self.section_name,
+            TestImage::SECTION_RVA,
+            self.section_characteristics.clone(),
+        );
+        let sections: Vec<(&Vec<u8>, u32, u32)> =
+            core::iter::once(first)
+                .chain(self.sections.iter().map(|(name, rva, characteristics)| {
+                    (name, rva.clone(), characteristics.clone())
+                }))
+                .collect();
+        for (index, (name, rva, characteristics)) in sections.iter().enumerate() {
+            let end = sections
+                .get(index + 1)
+                .map_or(TestImage::SIZE_OF_IMAGE, |(_, rva, _)| rva.clone());
+            let offset = rva - TestImage::SECTION_RVA;
+            let raw_size = self.raw_size.saturating_sub(offset).min(end - rva);
+            let raw_offset = match raw_size {
+                0 => 0,
+                _ => self.raw_offset.clone() + offset,
+            };
+            let section_header = optional_header + size_of_optional_header + index * 40;
+            put(&mut bytes, section_header, name);
+            put_u32(&mut bytes, section_header + 8, end - rva);
+            put_u32(&mut bytes, section_header + 12, rva.clone());
+            put_u32(&mut bytes, section_header + 16, raw_size);
+            put_u32(&mut bytes, section_header + 20, raw_offset);
+            put_u32(&mut bytes, section_header + 36, characteristics.clone());
+        }
+
+        // Export directory followed by its tables and strings.
+        let mut names: Vec<(&str, u16)> = self
+            .functions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (name, _))| name.as_deref().map(|name| (name, index as u16)))
+            .collect();
+        names.sort();
+
+        let directory = TestImage::SECTION_RVA as usize;
+        let functions = directory + 40;
+        let name_pointers = functions + self.functions.len() * 4;
+        let name_ordinals = name_pointers + names.len() * 4;
+        let mut strings = name_ordinals + names.len() * 2;
+
+        let dll_name = put_str(&mut bytes, &mut strings, &self.dll_name);
+        put_u32(&mut bytes, directory + 12, dll_name);
+        put_u32(&mut bytes, directory + 16, self.ordinal_base.clone());
+        put_u32(&mut bytes, directory + 20, self.functions.len() as u32);
+        put_u32(&mut bytes, directory + 24, names.len() as u32);
+        put_u32(&mut bytes, directory + 28, functions as u32);
+        put_u32(&mut bytes, directory + 32, name_pointers as u32);
+        put_u32(&mut bytes, directory + 36, name_ordinals as u32);
+
+        for (index, (_, export)) in self.functions.iter().enumerate() {
+            let rva = match export {
+                TestExport::Code(rva) => rva.clone(),
+                TestExport::Forwarder(forwarder) => put_str(&mut bytes, &mut strings, forwarder),
+            };
+            put_u32(&mut bytes, functions + index * 4, rva);
+        }
+        for (index, (name, ordinal)) in names.iter().enumerate() {
+            let name = put_str(&mut bytes, &mut strings, name);
+            put_u32(&mut bytes, name_pointers + index * 4, name);
+            put_u16(&mut bytes, name_ordinals + index * 2, ordinal.clone());
+        }
+        assert!(strings.le(&(TestImage::CODE_RVA as usize)));
+
+        // The export data directory, the first one.
+        put_u32(&mut bytes, optional_header + 112, directory as u32);
+        put_u32(
+            &mut bytes,
+            optional_header + 116,
+            (strings - directory) as u32,
+        );
+        for (index, rva, size) in &self.data_directories {
+            assert!(index.lt(&data_directories));
+            let data_directory = optional_header + 112 + index * 8;
+            put_u32(&mut bytes, data_directory, rva.clone());
+            put_u32(&mut bytes, data_directory + 4, size.clone());
+        }
+        for (rva, contents) in &self.contents {
+            let rva = rva.clone() as usize;
+            assert!(rva.ge(&strings) && (rva + contents.len()).le(&bytes.len()));
+            put(&mut bytes, rva, contents);
+        }
+
+        // Past the raw data the loader maps zeros.
+        let raw_end = (TestImage::SECTION_RVA + self.raw_size.clone()) as usize;
+        bytes[raw_end..].fill(0);
+        bytes
+    }
+}
+
+fn put(bytes: &mut [u8], offset: usize, value: &[u8]) {
+    bytes[offset..offset + value.len()].copy_from_slice(value);
+}
+
+fn put_u16(bytes: &mut [u8], offset: usize, value: u16) {
+    put(bytes, offset, &value.to_le_bytes());
+}
+
+fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+    put(bytes, offset, &value.to_le_bytes());
+}
+
+fn put_u64(bytes: &mut [u8], offset: usize, value: u64) {
+    put(bytes, offset, &value.to_le_bytes());
+}
+
+/// Writes a NUL terminated string at `*offset` and returns its RVA.
+fn put_str(bytes: &mut [u8], offset: &mut usize, value: &str) -> u32 {
+    let rva = offset.clone() as u32;
+    put(bytes, *offset, value.as_bytes());
+    *offset += value.len() + 1;
+    rva
+}
+
+/// Builds an API set schema of `version` 2, 4 or 6. Each entry is a
+/// contract name as stored in the schema, i.e. without `.dll` and for
+/// versions 2 and 4 without the `api-` prefix, and its `(importer, host)`
+/// values.
+pub fn api_set_schema(version: u32, entries: &[(&str, &[(&str, &str)])]) -> Vec<u8> {
+    let (header_size, entry_size, value_header_size, value_size) = match version {
+        2 => (8, 12, 4, 16),
+        4 => (16, 24, 8, 20),
+        _ => (28, 24, 0, 20),
+    };
+    let values_size: usize = entries
+        .iter()
+        .map(|(_, values)| value_header_size + values.len() * value_size)
+        .sum();
+    let mut bytes = vec![0u8; header_size + entries.len() * entry_size + values_size];
+    let mut strings = Vec::new();
+    let strings_offset = bytes.len();
+    let mut put_name = |name: &str| -> (u32, u32) {
+        let offset = strings_offset + strings.len();
+        let units: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        strings.extend_from_slice(&units);
+        (offset as u32, units.len() as u32)
+    };
+
+    let mut data = header_size + entries.len() * entry_size;
+    for (index, (name, values)) in entries.iter().enumerate() {
+        let entry = header_size + index * entry_size;
+        let (name_offset, name_length) = put_name(name);
+        match version {
+            2 => {
+                put_u32(&mut bytes, entry, name_offset);
+                put_u32(&mut bytes, entry + 4, name_length);
+                put_u32(&mut bytes, entry + 8, data as u32);
+                put_u32(&mut bytes, data, values.len() as u32);
+            }
+            4 => {
+                put_u32(&mut bytes, entry + 4, name_offset);
+                put_u32(&mut bytes, entry + 8, name_length);
+                put_u32(&mut bytes, entry + 20, data as u32);
+                put_u32(&mut bytes, data + 4, values.len() as u32);
+            }
+            _ => {
+                // The hashed name leaves out the minor version.
+                let hashed_length = name.rfind('-').unwrap_or(name.len()) * 2;
+                put_u32(&mut bytes, entry + 4, name_offset);
+                put_u32(&mut bytes, entry + 8, name_length);
+                put_u32(&mut bytes, entry + 12, hashed_length as u32);
+                put_u32(&mut bytes, entry + 16, data as u32);
+                put_u32(&mut bytes, entry + 20, values.len() as u32);
+            }
+        }
+        let first_value = data + value_header_size;
+        for (index, (importer, host)) in values.iter().enumerate() {
+            let value = match version {
+                2 => first_value + index * value_size,
+                _ => first_value + index * value_size + 4,
+            };
+            let (importer_offset, importer_length) = put_name(importer);
+            let (host_offset, host_length) = put_name(host);
+            put_u32(&mut bytes, value, importer_offset);
+            put_u32(&mut bytes, value + 4, importer_length);
+            put_u32(&mut bytes, value + 8, host_offset);
+            put_u32(&mut bytes, value + 12, host_length);
+        }
+        data = first_value + values.len() * value_size;
+    }
+
+    bytes.extend_from_slice(&strings);
+    let size = bytes.len() as u32;
+    put_u32(&mut bytes, 0, version);
+    match version {
+        2 => put_u32(&mut bytes, 4, entries.len() as u32),
+        _ => {
+            put_u32(&mut bytes, 4, size);
+            put_u32(&mut bytes, 12, entries.len() as u32);
+            if version.eq(&6) {
+                put_u32(&mut bytes, 16, header_size as u32);
+            }
+        }
+    }
+    bytes
+}
+
+/// Lays out MSVC RTTI for 64-bit images the way the compiler emits it into
+/// `.rdata`: per class a type descriptor, a complete object locator, a class
+/// hierarchy descriptor with its base class array, and a vtable whose slot
+/// `-1` points at the locator.
+pub struct TestRtti {
+    rva: u32,
+    image_base: u64,
+    bytes: Vec<u8>,
+    classes: Vec<TestClass>,
+}
+
+/// RVAs of the RTTI structures written for a class.
+pub struct TestClass {
+    pub type_descriptor: u32,
+    pub complete_object_locator: u32,
+    pub class_hierarchy_descriptor: u32,
+    pub base_class_descriptor: u32,
+    pub vtable: u32,
+    /// The class followed by its bases in pre-order, as in the base class
+    /// array.
+    hierarchy: Vec<usize>,
+}
+
+impl TestRtti {
+    /// Structures are placed from `rva` on, which should be the start of a
+    /// readable, non-executable section so that every structure keeps its
+    /// natural alignment within it.
+    pub fn new(rva: u32, image_base: u64) -> Self {
+        Self {
+            rva,
+            image_base,
+            bytes: Vec::new(),
+            classes: Vec::new(),
+        }
+    }
+
+    /// Adds the class `decorated_name`, e.g. `.?AVFoo@@`, deriving from the
+    /// earlier classes `bases`, with a vtable of the code RVAs `methods`.
+    pub fn class(mut self: Self, decorated_name: &str, bases: &[usize], methods: &[u32]) -> Self {
+        let index = self.classes.len();
+        let mut hierarchy = vec![index];
+        for base in bases {
+            assert!(base.lt(&index));
+            hierarchy.extend_from_slice(&self.classes[base.clone()].hierarchy);
+        }
+
+        let type_descriptor = self.reserve(16 + decorated_name.len() + 1);
+        self.put(type_descriptor + 16, decorated_name.as_bytes());
+
+        let complete_object_locator = self.reserve(24);
+        let class_hierarchy_descriptor = self.reserve(16);
+        let base_class_array = self.reserve(hierarchy.len() * 4);
+        let base_class_descriptor = self.reserve(28);
+        self.put_u32s(
+            complete_object_locator,
+            &[
+                1,
+                0,
+                0,
+                type_descriptor,
+                class_hierarchy_descriptor,
+                complete_object_locator,
+            ],
+        );
+        self.put_u32s(
+            class_hierarchy_descriptor,
+            &[0, 0, hierarchy.len() as u32, base_class_array],
+        );
+        let contained_bases = hierarchy.len() as u32 - 1;
+        self.put_u32s(
+            base_class_descriptor,
+            &[
+                type_descriptor,
+                contained_bases,
+                0,
+                u32::MAX,
+                0,
+                0x40,
+                class_hierarchy_descriptor,
+            ],
+        );
+        self.classes.push(TestClass {
+            type_descriptor,
+            complete_object_locator,
+            class_hierarchy_descriptor,
+            base_class_descriptor,
+            vtable: 0,
+            hierarchy,
+        });
+        let descriptors: Vec<u32> = self.classes[index]
+            .hierarchy
+            .iter()
+            .map(|class| self.classes[class.clone()].base_class_descriptor)
+            .collect();
+        self.put_u32s(base_class_array, &descriptors);
+
+        // The locator slot, the methods and a null slot ending the vtable.
+        let slots = self.reserve((methods.len() + 2) * 8);
+        let locator = self.image_base + complete_object_locator as u64;
+        self.put(slots, &locator.to_le_bytes());
+        for (index, method) in methods.iter().enumerate() {
+            let method = self.image_base + method.clone() as u64;
+            self.put(slots + 8 + index as u32 * 8, &method.to_le_bytes());
+        }
+        self.classes[index].vtable = slots + 8;
+        self
+    }
+
+    pub fn classes(self: &Self) -> &[TestClass] {
+        &self.classes
+    }
+
+    /// The structures, to be placed at the `rva` given to [`TestRtti::new`].
+    pub fn bytes(self: &Self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn reserve(self: &mut Self, size: usize) -> u32 {
+        let rva = self.rva + self.bytes.len() as u32;
+        self.bytes
+            .resize(self.bytes.len() + size.next_multiple_of(8), 0);
+        rva
+    }
+
+    fn put(self: &mut Self, rva: u32, value: &[u8]) {
+        put(&mut self.bytes, (rva - self.rva) as usize, value);
+    }
+
+    fn put_u32s(self: &mut Self, rva: u32, values: &[u32]) {
+        for (index, value) in values.iter().enumerate() {
+            self.put(rva + index as u32 * 4, &value.to_le_bytes());
+        }
+    }
+}
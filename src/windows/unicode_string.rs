@@ -1,7 +1,13 @@
-use core::slice::from_raw_parts;
+#[cfg(any(feature = "alloc", test))]
+use alloc::string::String;
+use core::{
+    fmt::{self, Debug, Display, Formatter, Write},
+    slice::from_raw_parts,
+};
+
+use super::ModuleName;
 
 #[repr(C)]
-#[derive(Debug)]
 pub struct UnicodeString {
     length: u16,
     maximum_length: u16,
@@ -9,7 +15,101 @@ pub struct UnicodeString {
 }
 
 impl UnicodeString {
+    /// Empty strings may have no buffer at all.
     pub fn utf16(self: &Self) -> &[u16] {
+        if self.buffer.is_null() {
+            return &[];
+        }
         unsafe { from_raw_parts(self.buffer, (self.length / 2) as usize) }
     }
+
+    /// The characters of the string, with unpaired surrogates replaced by
+    /// `U+FFFD`.
+    pub fn chars(self: &Self) -> impl Iterator<Item = char> + use<'_> {
+        ModuleName::Utf16(self.utf16()).chars()
+    }
+
+    #[cfg(any(feature = "alloc", test))]
+    pub fn to_string_lossy(self: &Self) -> String {
+        self.chars().collect()
+    }
+
+    /// Unicode case-insensitive comparison with `other`, without allocating.
+    pub fn eq_ignore_case(self: &Self, other: &str) -> bool {
+        ModuleName::Utf16(self.utf16()).eq_ignore_case(&ModuleName::Utf8(other))
+    }
+
+    pub fn eq_ignore_ascii_case(self: &Self, other: &str) -> bool {
+        self.chars()
+            .map(|character| character.to_ascii_lowercase())
+            .eq(other
+                .chars()
+                .map(|character| character.to_ascii_lowercase()))
+    }
+}
+
+impl Display for UnicodeString {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        for character in self.chars() {
+            f.write_char(character)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for UnicodeString {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for character in self.chars() {
+            for character in character.escape_debug() {
+                f.write_char(character)?;
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, vec::Vec};
+    use core::ptr::null;
+
+    use super::UnicodeString;
+
+    fn unicode_string(buffer: &[u16]) -> UnicodeString {
+        UnicodeString {
+            length: (buffer.len() * 2) as u16,
+            maximum_length: (buffer.len() * 2) as u16,
+            buffer: buffer.as_ptr(),
+        }
+    }
+
+    #[test]
+    fn empty_string_without_buffer() {
+        let empty = UnicodeString {
+            length: 0,
+            maximum_length: 0,
+            buffer: null(),
+        };
+        assert!(empty.utf16().is_empty());
+        assert_eq!(format!("{}", empty), "");
+        assert!(empty.eq_ignore_case(""));
+    }
+
+    #[test]
+    fn formats_and_compares() {
+        let buffer: Vec<u16> = "KERNEL32.dll".encode_utf16().collect();
+        let name = unicode_string(&buffer);
+        assert_eq!(format!("{}", name), "KERNEL32.dll");
+        assert_eq!(format!("{:?}", name), "\"KERNEL32.dll\"");
+        assert!(name.eq_ignore_case("kernel32.DLL"));
+        assert!(name.eq_ignore_ascii_case("kernel32.DLL"));
+        assert!(!name.eq_ignore_case("kernel32"));
+    }
+
+    #[test]
+    fn replaces_unpaired_surrogates() {
+        let buffer = [u16::from(b'a'), 0xd800, u16::from(b'b')];
+        assert_eq!(format!("{}", unicode_string(&buffer)), "a\u{fffd}b");
+    }
 }
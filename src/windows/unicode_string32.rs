@@ -0,0 +1,42 @@
+use core::{
+    mem::{size_of, size_of_val},
+    slice::from_raw_parts_mut,
+};
+
+use super::MemoryReader;
+
+/// A `UNICODE_STRING32` whose buffer lives in another process.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct UnicodeString32 {
+    length: u16,
+    maximum_length: u16,
+    buffer: u32,
+}
+
+impl UnicodeString32 {
+    pub fn len(self: &Self) -> usize {
+        self.length as usize / size_of::<u16>()
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.length.eq(&0)
+    }
+
+    pub fn buffer(self: &Self) -> &u32 {
+        &self.buffer
+    }
+
+    /// Reads the string into `buffer`, failing if it does not fit.
+    pub fn read<'b>(
+        self: &Self,
+        reader: &(impl MemoryReader + ?Sized),
+        buffer: &'b mut [u16],
+    ) -> Option<&'b [u16]> {
+        let utf16 = buffer.get_mut(..self.len())?;
+        let bytes =
+            unsafe { from_raw_parts_mut(utf16.as_mut_ptr() as *mut u8, size_of_val(utf16)) };
+        reader.read(self.buffer as u64, bytes)?;
+        Some(utf16)
+    }
+}
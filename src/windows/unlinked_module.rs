@@ -0,0 +1,51 @@
+use core::fmt::{self, Display, Formatter};
+
+use super::{LdrDataTableEntry, LoaderOrder, ModuleName};
+
+/// A module threaded onto `listed_in` but missing from `missing_from`, as
+/// left behind by code that unlinks its DLL to hide it.
+#[derive(Debug, Clone, Copy)]
+pub struct UnlinkedModule {
+    entry: &'static LdrDataTableEntry,
+    listed_in: LoaderOrder,
+    missing_from: LoaderOrder,
+}
+
+impl UnlinkedModule {
+    pub fn new(
+        entry: &'static LdrDataTableEntry,
+        listed_in: LoaderOrder,
+        missing_from: LoaderOrder,
+    ) -> Self {
+        Self {
+            entry,
+            listed_in,
+            missing_from,
+        }
+    }
+
+    pub fn entry(self: &Self) -> &'static LdrDataTableEntry {
+        self.entry
+    }
+
+    pub fn listed_in(self: &Self) -> &LoaderOrder {
+        &self.listed_in
+    }
+
+    pub fn missing_from(self: &Self) -> &LoaderOrder {
+        &self.missing_from
+    }
+}
+
+impl Display for UnlinkedModule {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {:p} is in the {:?} order list but not in the {:?} order list",
+            ModuleName::Utf16(self.entry.base_dll_name()),
+            self.entry.dll_base(),
+            self.listed_in,
+            self.missing_from,
+        )
+    }
+}